@@ -0,0 +1,412 @@
+//! Times the ckey lookups used by the API and checks that MySQL resolves each of them through an
+//! index. Every case calls the real `database` function, so the queries can't drift from the ones
+//! the routes run.
+//!
+//! A lookup counts as a full scan when one warm call reads more than `SCAN_LIMIT` rows without an
+//! index, going by the server-wide `Handler_read_rnd_next` counter. Keep other clients off the
+//! server while it runs.
+//!
+//! Point `DATABASE_URL` at a scratch database, the tables in `tests/fixtures/game_schema.sql` and
+//! `database_schema.sql` are dropped and reseeded:
+//!
+//! ```sh
+//! DATABASE_URL=mysql://root@127.0.0.1/psychonaut_bench cargo bench --bench ckey_queries
+//! ```
+
+use std::{
+    env,
+    process::ExitCode,
+    str::FromStr as _,
+    time::{Duration, Instant},
+};
+
+use psychonaut_api::{
+    cache::Cache,
+    ckey::Ckey,
+    config::Config,
+    database::{error::Error, *},
+};
+use sqlx::{
+    mysql::{MySqlConnectOptions, MySqlPoolOptions},
+    Executor as _, MySqlPool, QueryBuilder, Row as _,
+};
+
+const GAME_SCHEMA: &str = include_str!("../tests/fixtures/game_schema.sql");
+const API_SCHEMA: &str = include_str!("../database_schema.sql");
+
+const PLAYERS: usize = 20_000;
+const ITERATIONS: u32 = 50;
+const SCAN_LIMIT: u64 = 1_000;
+const TARGET_CKEY: &str = "player10000";
+const FRIEND_CKEY: &str = "player10001";
+
+const CASES: &[&str] = &[
+    "get_player",
+    "get_roletime",
+    "get_player_rounds",
+    "get_messages",
+    "get_notes",
+    "get_ban",
+    "get_tickets",
+    "get_achievements",
+    "get_activity",
+    "get_ckeys",
+    "get_friends",
+    "get_friendship_invites",
+    "check_friendship",
+];
+
+struct Context {
+    pool: MySqlPool,
+    config: Config,
+    cache: Cache,
+    ckey: Ckey,
+    friend: Ckey,
+}
+
+async fn lookup(case: &str, cx: &Context) -> Result<(), Error> {
+    let pool = &cx.pool;
+    let ckey = &cx.ckey;
+
+    match case {
+        "get_player" => drop(get_player(ckey, pool).await?),
+        "get_roletime" => drop(get_roletime(ckey, pool).await?),
+        "get_player_rounds" => drop(get_player_rounds(ckey, None, None, pool).await?),
+        "get_messages" => drop(get_messages(ckey, None, None, pool).await?),
+        "get_notes" => drop(get_notes(ckey, None, None, pool).await?),
+        "get_ban" => drop(get_ban(ckey, false, None, pool).await?),
+        "get_tickets" => drop(get_tickets(ckey, None, None, pool).await?),
+        "get_achievements" => drop(get_achievements(ckey, None, &cx.cache, pool).await?),
+        "get_activity" => drop(get_activity(ckey, 180, pool).await?),
+        "get_ckeys" => drop(get_ckeys(ckey, pool, &cx.config).await?),
+        "get_friends" => drop(get_friends(ckey, pool, &cx.config).await?),
+        "get_friendship_invites" => drop(get_friendship_invites(ckey, pool, &cx.config).await?),
+        "check_friendship" => drop(check_friendship(ckey, &cx.friend, pool, &cx.config).await?),
+        _ => unreachable!("unknown case {case}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let Ok(url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL is not set, skipping ckey query benchmark");
+        return ExitCode::SUCCESS;
+    };
+
+    match run(&url).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("benchmark failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(url: &str) -> Result<bool, Error> {
+    let options = MySqlConnectOptions::from_str(url)?;
+    let database = options.get_database().unwrap_or_default().to_string();
+
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+
+    // the game and API tables share the scratch database
+    let config: Config = toml::from_str(&format!(
+        r#"
+        address = "127.0.0.1"
+        port = 0
+        secret = ""
+        dev_secret = ""
+        dev_routes = []
+        exposed_secret = ""
+        exposed_routes = []
+        cli_colors = false
+        log_level = "off"
+        servers = []
+
+        [discord]
+        token = ""
+        guild = 0
+        patreon_role = 0
+
+        [database]
+        user = ""
+        password = ""
+        host = "127.0.0.1"
+        port = 3306
+        game_database = "{database}"
+        api_database = "{database}"
+        "#
+    ))
+    .expect("benchmark config is valid");
+
+    println!("seeding {PLAYERS} players...");
+    seed(&pool).await?;
+
+    let cache = Cache::new(&config.cache)
+        .await
+        .expect("the in-memory cache always builds");
+
+    let cx = Context {
+        pool,
+        config,
+        cache,
+        ckey: Ckey::new(TARGET_CKEY).expect("target ckey is valid"),
+        friend: Ckey::new(FRIEND_CKEY).expect("friend ckey is valid"),
+    };
+
+    let mut all_indexed = true;
+
+    println!("{:<24} {:>14} {:>12}", "query", "rows scanned", "time");
+
+    for case in CASES {
+        // the first call warms up anything the lookup caches
+        lookup(case, &cx).await?;
+
+        let scanned = rows_scanned(case, &cx).await?;
+        let indexed = scanned <= SCAN_LIMIT;
+        all_indexed &= indexed;
+
+        let started = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            lookup(case, &cx).await?;
+        }
+
+        let elapsed: Duration = started.elapsed() / ITERATIONS;
+
+        println!(
+            "{:<24} {:>14} {:>10.3}ms{}",
+            case,
+            scanned,
+            elapsed.as_secs_f64() * 1000.0,
+            if indexed { "" } else { "  FULL SCAN" },
+        );
+    }
+
+    Ok(all_indexed)
+}
+
+async fn read_rnd_next(pool: &MySqlPool) -> Result<u64, Error> {
+    let row = pool
+        .fetch_one("SHOW GLOBAL STATUS LIKE 'Handler_read_rnd_next'")
+        .await?;
+    let value: String = row.try_get(1)?;
+
+    Ok(value.parse().unwrap_or_default())
+}
+
+/// Rows read without an index by one call, less what reading the counter itself costs.
+async fn rows_scanned(case: &str, cx: &Context) -> Result<u64, Error> {
+    let before = read_rnd_next(&cx.pool).await?;
+    let noise = read_rnd_next(&cx.pool).await? - before;
+
+    let before = read_rnd_next(&cx.pool).await?;
+    lookup(case, cx).await?;
+    let after = read_rnd_next(&cx.pool).await?;
+
+    Ok((after - before).saturating_sub(noise))
+}
+
+/// Splits a SQL script into statements on semicolons that end a line.
+fn statements(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+
+    for line in script.lines() {
+        if line.trim_start().starts_with("--") {
+            continue;
+        }
+
+        current.push_str(line);
+        current.push('\n');
+
+        if line.trim_end().ends_with(';') {
+            statements.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current);
+    }
+
+    statements
+}
+
+async fn seed(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    for statement in statements(GAME_SCHEMA)
+        .into_iter()
+        .chain(statements(API_SCHEMA))
+    {
+        pool.execute(statement.as_str()).await?;
+    }
+
+    const JOBS: [&str; 5] = [
+        "Assistant",
+        "Cook",
+        "Medical Doctor",
+        "Security Officer",
+        "Captain",
+    ];
+
+    for chunk in (0..PLAYERS).collect::<Vec<_>>().chunks(1_000) {
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO player (ckey, byond_key, firstseen, lastseen, ip, computerid) ",
+        );
+        builder.push_values(chunk, |mut b, i| {
+            b.push_bind(format!("player{i}"))
+                .push_bind(format!("Player{i}"))
+                .push("NOW()")
+                .push("NOW()")
+                .push_bind(*i as u32)
+                .push_bind(format!("{}", 1_000_000 + i));
+        });
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new("INSERT INTO role_time (ckey, job, minutes) ");
+        builder.push_values(
+            chunk
+                .iter()
+                .flat_map(|i| JOBS.iter().map(move |job| (i, job))),
+            |mut b, (i, job)| {
+                b.push_bind(format!("player{i}"))
+                    .push_bind(*job)
+                    .push_bind((*i % 600) as u32);
+            },
+        );
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO manifest (server_ip, server_port, round_id, ckey, character_name, job, timestamp) ",
+        );
+        builder.push_values(
+            chunk
+                .iter()
+                .flat_map(|i| (0..10).map(move |round| (i, round))),
+            |mut b, (i, round)| {
+                b.push_bind(0)
+                    .push_bind(1337)
+                    .push_bind(round)
+                    .push_bind(format!("player{i}"))
+                    .push_bind(format!("Character {i}"))
+                    .push_bind(JOBS[round as usize % JOBS.len()])
+                    .push("NOW()");
+            },
+        );
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO messages (type, targetckey, adminckey, text, timestamp, server_ip, server_port, secret) ",
+        );
+        builder.push_values(
+            chunk
+                .iter()
+                .flat_map(|i| ["note", "message"].map(|kind| (i, kind))),
+            |mut b, (i, kind)| {
+                b.push_bind(kind)
+                    .push_bind(format!("player{i}"))
+                    .push_bind("admin")
+                    .push_bind("text")
+                    .push("NOW()")
+                    .push_bind(0)
+                    .push_bind(1337)
+                    .push_bind(0);
+            },
+        );
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO ban (bantime, server_ip, server_port, role, reason, ckey, a_ckey, a_ip, a_computerid, who, adminwho) ",
+        );
+        builder.push_values(chunk.iter().filter(|i| *i % 10 == 0), |mut b, i| {
+            b.push("NOW()")
+                .push_bind(0)
+                .push_bind(1337)
+                .push_bind("Server")
+                .push_bind("reason")
+                .push_bind(format!("player{i}"))
+                .push_bind("admin")
+                .push_bind(0)
+                .push_bind("0")
+                .push_bind("")
+                .push_bind("");
+        });
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO ticket (server_ip, server_port, round_id, ticket, action, message, timestamp, recipient, sender) ",
+        );
+        builder.push_values(chunk, |mut b, i| {
+            b.push_bind(0)
+                .push_bind(1337)
+                .push_bind(*i as u32 % 100)
+                .push_bind(*i as u32 % 50)
+                .push_bind("Ticket Opened")
+                .push_bind("help")
+                .push("NOW()")
+                .push_bind(None::<String>)
+                .push_bind(format!("player{i}"));
+        });
+        builder.build().execute(pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO connection_log (datetime, server_ip, server_port, round_id, ckey, ip, computerid) ",
+        );
+        builder.push_values(
+            chunk
+                .iter()
+                .flat_map(|i| (0..10).map(move |round| (i, round))),
+            |mut b, (i, round)| {
+                b.push("NOW()")
+                    .push_bind(0)
+                    .push_bind(1337)
+                    .push_bind(round)
+                    .push_bind(format!("player{i}"))
+                    .push_bind(*i as u32)
+                    .push_bind(format!("{}", 1_000_000 + i));
+            },
+        );
+        builder.build().execute(pool).await?;
+
+        let mut builder =
+            QueryBuilder::new("INSERT INTO achievements (ckey, achievement_key, value) ");
+        builder.push_values(
+            chunk
+                .iter()
+                .flat_map(|i| ["Clean Shift", "Meteors", "Tendril"].map(|key| (i, key))),
+            |mut b, (i, key)| {
+                b.push_bind(format!("player{i}"))
+                    .push_bind(key)
+                    .push_bind(1);
+            },
+        );
+        builder.build().execute(pool).await?;
+
+        let mut builder =
+            QueryBuilder::new("INSERT INTO hid_ckeys_autocomplete (ckey, hid_by, valid) ");
+        builder.push_values(chunk.iter().filter(|i| *i % 2 == 1), |mut b, i| {
+            b.push_bind(format!("player{i}"))
+                .push_bind(0)
+                .push_bind(true);
+        });
+        builder.build().execute(pool).await?;
+
+        let mut builder =
+            QueryBuilder::new("INSERT INTO friendship (user_ckey, friend_ckey, status) ");
+        builder.push_values(chunk.iter().filter(|i| *i % 2 == 0), |mut b, i| {
+            b.push_bind(format!("player{i}"))
+                .push_bind(format!("player{}", i + 1))
+                .push_bind("accepted");
+        });
+        builder.build().execute(pool).await?;
+    }
+
+    pool.execute("ANALYZE TABLE player, role_time, manifest, messages, ban, ticket, connection_log, achievements, hid_ckeys_autocomplete, friendship")
+        .await?;
+
+    Ok(())
+}
@@ -23,7 +23,8 @@ CREATE TABLE `hid_ckeys_autocomplete` (
 	`unhid_by` BIGINT(20) NULL DEFAULT NULL,
 	`timestamp` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
 	`valid` BOOLEAN NOT NULL DEFAULT FALSE,
-	PRIMARY KEY (`id`)
+	PRIMARY KEY (`id`),
+	INDEX `idx_hid_ckeys_ckey` (`ckey`, `valid`)
 ) COLLATE='utf8mb4_general_ci' ENGINE=InnoDB;
 /*!40101 SET character_set_client = @saved_cs_client */;
 
@@ -47,6 +48,8 @@ CREATE TABLE `friendship` (
   ) VIRTUAL,
   PRIMARY KEY (`id`),
   UNIQUE INDEX `unique_constraints` (`unique_pair`),
+  INDEX `idx_friendship_user` (`user_ckey`, `status`),
+  INDEX `idx_friendship_friend` (`friend_ckey`, `status`),
   CHECK (user_ckey <> friend_ckey)
 ) COLLATE='utf8mb4_general_ci' ENGINE=InnoDB;
 /*!40101 SET character_set_client = @saved_cs_client */;
\ No newline at end of file
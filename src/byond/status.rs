@@ -1,16 +1,13 @@
-use std::{
-    str::FromStr,
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{str::FromStr, time::Duration};
 
-use once_cell::sync::Lazy;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use serde_repr::Serialize_repr;
-use tokio::sync::RwLock;
 
-use crate::config::{Config, Server};
+use crate::{
+    cache::Cache,
+    config::{Config, Server},
+};
 
 use super::{topic, Error, Response};
 
@@ -190,12 +187,7 @@ pub async fn status(address: &str) -> super::Result<ServerStatus> {
     Err(Error::UnexpectedType(response))
 }
 
-type ServerStatusCache = Option<(Instant, Vec<Status>)>;
-
-static LAST_SERVER_STATUS: Lazy<Arc<RwLock<ServerStatusCache>>> =
-    Lazy::new(|| Arc::new(RwLock::new(None)));
-
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Status(pub Value);
 
 impl Status {
@@ -221,16 +213,29 @@ impl Status {
     }
 }
 
-pub async fn get_server_status(config: &Config) -> Vec<Status> {
-    {
-        let last_server_status = LAST_SERVER_STATUS.read().await;
-        if let Some((last_update, server_status)) = &*last_server_status {
-            if last_update.elapsed() < Duration::from_secs(30) {
-                return server_status.clone();
+const SERVER_STATUS_TTL: Duration = Duration::from_secs(30);
+
+pub async fn get_server_status(config: &Config, cache: &Cache) -> Vec<Status> {
+    cache
+        .server_status
+        .get_or_try_load((), || async {
+            if let Some(status) = cache.get_shared("server_status").await {
+                return Ok(status);
             }
-        }
-    }
 
+            let status = fetch_server_status(config).await?;
+            cache
+                .set_shared("server_status", &status, SERVER_STATUS_TTL)
+                .await;
+
+            Ok(status)
+        })
+        .await
+        .unwrap_or_else(|uncached| uncached)
+}
+
+/// Returns `Err` with the statuses when no server responded so they are not cached.
+async fn fetch_server_status(config: &Config) -> Result<Vec<Status>, Vec<Status>> {
     let mut should_cache = false;
     let mut response = Vec::new();
 
@@ -245,9 +250,8 @@ pub async fn get_server_status(config: &Config) -> Vec<Status> {
     }
 
     if should_cache {
-        let mut last_server_status = LAST_SERVER_STATUS.write().await;
-        *last_server_status = Some((Instant::now(), response.clone()));
+        Ok(response)
+    } else {
+        Err(response)
     }
-
-    response
 }
@@ -0,0 +1,53 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::{Backend, Error};
+
+#[derive(Default)]
+pub struct MemoryBackend {
+    values: Mutex<HashMap<String, (String, Instant)>>,
+    counters: Mutex<HashMap<String, (u64, Instant)>>,
+}
+
+#[rocket::async_trait]
+impl Backend for MemoryBackend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let values = self.values.lock().unwrap();
+
+        Ok(values
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(value, _)| value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Error> {
+        let mut values = self.values.lock().unwrap();
+        let now = Instant::now();
+
+        values.retain(|_, (_, expires)| *expires > now);
+        values.insert(key.to_string(), (value, now + ttl));
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        self.values.lock().unwrap().remove(key);
+
+        Ok(())
+    }
+
+    async fn increment(&self, key: &str, window: Duration) -> Result<u64, Error> {
+        let mut counters = self.counters.lock().unwrap();
+        let now = Instant::now();
+
+        counters.retain(|_, (_, expires)| *expires > now);
+
+        let (count, _) = counters.entry(key.to_string()).or_insert((0, now + window));
+        *count += 1;
+
+        Ok(*count)
+    }
+}
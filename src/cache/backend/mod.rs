@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use super::Error;
+
+mod memory;
+mod redis;
+
+pub use self::redis::RedisBackend;
+pub use memory::MemoryBackend;
+
+/// Storage shared by every API instance pointed at the same backend.
+#[rocket::async_trait]
+pub trait Backend: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error>;
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Error>;
+
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+
+    /// Increments the counter at `key`, starting a new `window` when it does not exist yet.
+    async fn increment(&self, key: &str, window: Duration) -> Result<u64, Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn exercise(backend: &dyn Backend) {
+        backend.delete("test:value").await.unwrap();
+        assert_eq!(backend.get("test:value").await.unwrap(), None);
+
+        backend
+            .set("test:value", "1".to_string(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get("test:value").await.unwrap(),
+            Some("1".to_string())
+        );
+
+        backend.delete("test:value").await.unwrap();
+        assert_eq!(backend.get("test:value").await.unwrap(), None);
+
+        backend
+            .set("test:expiring", "1".to_string(), Duration::from_millis(50))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(backend.get("test:expiring").await.unwrap(), None);
+
+        let window = Duration::from_millis(200);
+        let first = backend.increment("test:counter", window).await.unwrap();
+        let second = backend.increment("test:counter", window).await.unwrap();
+        assert_eq!(second, first + 1);
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(backend.increment("test:counter", window).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn memory_backend() {
+        exercise(&MemoryBackend::default()).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a redis-server, set REDIS_URL to override the default address"]
+    async fn redis_backend() {
+        let url = std::env::var("REDIS_URL").unwrap_or("redis://127.0.0.1:6379".to_string());
+        let backend = RedisBackend::connect(&url).await.unwrap();
+
+        exercise(&backend).await;
+    }
+}
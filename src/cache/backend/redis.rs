@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use redis::{aio::ConnectionManager, AsyncCommands as _, Client, Script};
+
+use super::{Backend, Error};
+
+const KEY_PREFIX: &str = "psychonaut-api:";
+
+/// Runs atomically, so a counter can't be left behind without an expiry.
+const INCREMENT: &str = r"
+local count = redis.call('INCR', KEYS[1])
+if redis.call('PTTL', KEYS[1]) < 0 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[1])
+end
+return count
+";
+
+pub struct RedisBackend {
+    connection: ConnectionManager,
+}
+
+impl RedisBackend {
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        let client = Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+
+        Ok(Self { connection })
+    }
+}
+
+#[rocket::async_trait]
+impl Backend for RedisBackend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let mut connection = self.connection.clone();
+
+        Ok(connection.get(format!("{KEY_PREFIX}{key}")).await?)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Error> {
+        let mut connection = self.connection.clone();
+
+        connection
+            .pset_ex::<_, _, ()>(format!("{KEY_PREFIX}{key}"), value, ttl.as_millis() as u64)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        let mut connection = self.connection.clone();
+
+        connection
+            .del::<_, ()>(format!("{KEY_PREFIX}{key}"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn increment(&self, key: &str, window: Duration) -> Result<u64, Error> {
+        let mut connection = self.connection.clone();
+        let key = format!("{KEY_PREFIX}{key}");
+
+        let count: u64 = Script::new(INCREMENT)
+            .key(key)
+            .arg(window.as_millis() as u64)
+            .invoke_async(&mut connection)
+            .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub enum Error {
+    Redis(#[from] redis::RedisError),
+    SerdeJson(#[from] serde_json::Error),
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+use crate::{
+    byond::Status,
+    config,
+    database::{AchievementRarity, JobRoletime, Overview, TestMerge},
+    http::discord::User,
+};
+
+mod backend;
+mod error;
+mod ttl;
+
+pub use backend::*;
+pub use error::Error;
+pub use ttl::*;
+
+pub struct Cache {
+    backend: Box<dyn Backend>,
+    pub server_status: TtlCache<(), Vec<Status>>,
+    pub recent_test_merges: TtlCache<(), Vec<TestMerge>>,
+    pub top_roletime: TtlCache<String, Vec<JobRoletime>>,
+    pub overview: TtlCache<i32, Vec<Overview>>,
+    pub patrons: TtlCache<(), Vec<String>>,
+    pub discord_users: TtlCache<i64, User>,
+    /// Keyed by the activity window in days.
+    pub achievement_rarities: TtlCache<u32, Vec<AchievementRarity>>,
+}
+
+impl Cache {
+    pub async fn new(config: &config::Cache) -> Result<Self, Error> {
+        let backend: Box<dyn Backend> = match config {
+            config::Cache::Memory => Box::new(MemoryBackend::default()),
+            config::Cache::Redis { url } => Box::new(RedisBackend::connect(url).await?),
+        };
+
+        Ok(Self {
+            backend,
+            // shared values are only coalesced locally, the backend holds the snapshot
+            server_status: TtlCache::new(Duration::from_secs(2)),
+            recent_test_merges: TtlCache::new(Duration::from_secs(2))
+                .stale_for(Duration::from_secs(600)),
+            top_roletime: TtlCache::new(Duration::from_secs(300)).capacity(256),
+            overview: TtlCache::new(Duration::from_secs(60))
+                .stale_for(Duration::from_secs(60))
+                .capacity(16),
+            patrons: TtlCache::new(Duration::from_secs(2)),
+            discord_users: TtlCache::new(Duration::from_secs(600)).capacity(1024),
+            achievement_rarities: TtlCache::new(Duration::from_secs(600))
+                .stale_for(Duration::from_secs(600))
+                .capacity(16),
+        })
+    }
+
+    /// Reads a shared value, treating backend failures as a miss.
+    pub async fn get_shared<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = match self.backend.get(key).await {
+            Ok(value) => value?,
+            Err(e) => {
+                warn!("Failed to read {key} from cache backend: {e}");
+                return None;
+            }
+        };
+
+        serde_json::from_str(&value).ok()
+    }
+
+    pub async fn set_shared<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let result = match serde_json::to_string(value) {
+            Ok(value) => self.backend.set(key, value, ttl).await,
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to write {key} to cache backend: {e}");
+        }
+    }
+
+    pub async fn invalidate_shared(&self, key: &str) {
+        if let Err(e) = self.backend.delete(key).await {
+            warn!("Failed to delete {key} from cache backend: {e}");
+        }
+    }
+
+    pub async fn increment(&self, key: &str, window: Duration) -> Result<u64, Error> {
+        self.backend.increment(key, window).await
+    }
+}
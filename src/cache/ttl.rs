@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex as AsyncMutex;
+
+struct Entry<V> {
+    value: V,
+    inserted: Instant,
+}
+
+/// Keyed cache whose entries expire after a fixed TTL.
+///
+/// Concurrent misses for the same key are coalesced so only one caller runs
+/// the loader while the rest wait for its result. Entries past their TTL but
+/// still inside the stale window are served as-is while a single caller
+/// refreshes them.
+pub struct TtlCache<K, V> {
+    entries: Mutex<HashMap<K, Entry<V>>>,
+    loading: Mutex<HashMap<K, Arc<AsyncMutex<()>>>>,
+    ttl: Duration,
+    stale: Duration,
+    capacity: usize,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            loading: Mutex::new(HashMap::new()),
+            ttl,
+            stale: Duration::ZERO,
+            capacity: 1,
+        }
+    }
+
+    pub fn stale_for(mut self, stale: Duration) -> Self {
+        self.stale = stale;
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub async fn get_or_try_load<E, F, Fut>(&self, key: K, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let stale = match self.lookup(&key) {
+            Lookup::Fresh(value) => return Ok(value),
+            Lookup::Stale(value) => Some(value),
+            Lookup::Missing => None,
+        };
+
+        let lock = self.loading_lock(&key);
+
+        let _guard = match stale {
+            Some(value) => match lock.try_lock() {
+                Ok(guard) => guard,
+                Err(_) => return Ok(value),
+            },
+            None => {
+                let guard = lock.lock().await;
+
+                if let Lookup::Fresh(value) = self.lookup(&key) {
+                    return Ok(value);
+                }
+
+                guard
+            }
+        };
+
+        let result = load().await;
+
+        if let Ok(value) = &result {
+            self.insert(key.clone(), value.clone());
+        }
+
+        self.release_loading_lock(&key, &lock);
+
+        result
+    }
+
+    pub fn invalidate(&self, key: &K) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    fn lookup(&self, key: &K) -> Lookup<V> {
+        let entries = self.entries.lock().unwrap();
+
+        let Some(entry) = entries.get(key) else {
+            return Lookup::Missing;
+        };
+
+        let age = entry.inserted.elapsed();
+
+        if age < self.ttl {
+            Lookup::Fresh(entry.value.clone())
+        } else if age < self.ttl + self.stale {
+            Lookup::Stale(entry.value.clone())
+        } else {
+            Lookup::Missing
+        }
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let max_age = self.ttl + self.stale;
+            entries.retain(|_, entry| entry.inserted.elapsed() < max_age);
+
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted)
+                    .map(|(key, _)| key.clone());
+
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                value,
+                inserted: Instant::now(),
+            },
+        );
+    }
+
+    fn loading_lock(&self, key: &K) -> Arc<AsyncMutex<()>> {
+        self.loading
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone()
+    }
+
+    fn release_loading_lock(&self, key: &K, lock: &Arc<AsyncMutex<()>>) {
+        let mut loading = self.loading.lock().unwrap();
+
+        if loading.get(key).is_some_and(|l| Arc::ptr_eq(l, lock)) {
+            loading.remove(key);
+        }
+    }
+}
+
+enum Lookup<V> {
+    Fresh(V),
+    Stale(V),
+    Missing,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rocket::futures::future::join_all;
+
+    use super::*;
+
+    async fn load(cache: &TtlCache<u32, u32>, key: u32, calls: &AtomicUsize) -> u32 {
+        cache
+            .get_or_try_load(key, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok::<_, ()>(key * 10)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn concurrent_misses_are_coalesced() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        let values = join_all((0..16).map(|_| load(&cache, 1, &calls))).await;
+
+        assert!(values.iter().all(|value| *value == 10));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let cache = TtlCache::new(Duration::from_millis(50));
+        let calls = AtomicUsize::new(0);
+
+        load(&cache, 1, &calls).await;
+        load(&cache, 1, &calls).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        load(&cache, 1, &calls).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn oldest_entry_is_evicted_at_capacity() {
+        let cache = TtlCache::new(Duration::from_secs(60)).capacity(2);
+        let calls = AtomicUsize::new(0);
+
+        for key in 1..=3 {
+            load(&cache, key, &calls).await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        load(&cache, 3, &calls).await;
+        load(&cache, 2, &calls).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        load(&cache, 1, &calls).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn failed_loads_are_not_cached() {
+        let cache: TtlCache<u32, u32> = TtlCache::new(Duration::from_secs(60));
+
+        let result = cache.get_or_try_load(1, || async { Err("offline") }).await;
+        assert_eq!(result, Err("offline"));
+
+        let result = cache
+            .get_or_try_load(1, || async { Ok::<_, &str>(5) })
+            .await;
+        assert_eq!(result, Ok(5));
+    }
+}
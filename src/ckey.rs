@@ -0,0 +1,121 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use rocket::form::{self, FromFormField, ValueField};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::{encode::IsNull, mysql::MySqlTypeInfo, Encode, MySql, Type};
+use thiserror::Error;
+
+pub const MAX_CKEY_LENGTH: usize = 32;
+
+/// A BYOND key in its canonical form: lowercase with everything but ASCII letters and digits removed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Ckey(String);
+
+impl Ckey {
+    pub fn new(key: &str) -> Result<Self, Error> {
+        let ckey: String = key
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        if ckey.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        if ckey.len() > MAX_CKEY_LENGTH {
+            return Err(Error::TooLong);
+        }
+
+        Ok(Self(ckey))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Ckey {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Ckey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Ckey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ckey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let key = String::deserialize(deserializer)?;
+        Self::new(&key).map_err(serde::de::Error::custom)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromFormField<'r> for Ckey {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        Ok(Self::new(field.value).map_err(|e| form::Error::validation(e.to_string()))?)
+    }
+}
+
+impl Type<MySql> for Ckey {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <str as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for Ckey {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        <&str as Encode<MySql>>::encode(self.as_str(), buf)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("ckey is empty")]
+    Empty,
+    #[error("ckey is longer than {MAX_CKEY_LENGTH} characters")]
+    TooLong,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalises_keys() {
+        assert_eq!(Ckey::new("Some Player").unwrap().as_str(), "someplayer");
+        assert_eq!(Ckey::new("someplayer").unwrap().as_str(), "someplayer");
+        assert_eq!(Ckey::new("Guest-1234").unwrap().as_str(), "guest1234");
+        assert_eq!(Ckey::new("_Mr. Ünïcode_").unwrap().as_str(), "mrncode");
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(matches!(Ckey::new(""), Err(Error::Empty)));
+        assert!(matches!(Ckey::new(" -_ "), Err(Error::Empty)));
+        assert!(matches!(Ckey::new(&"a".repeat(33)), Err(Error::TooLong)));
+        assert!(Ckey::new(&"a".repeat(32)).is_ok());
+    }
+}
@@ -1,6 +1,10 @@
 use rocket::config::LogLevel;
 use serde::Deserialize;
-use std::{collections::HashSet, fs::read_to_string, net::IpAddr};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::read_to_string,
+    net::IpAddr,
+};
 use thiserror::Error;
 
 #[derive(Debug, Deserialize)]
@@ -12,10 +16,26 @@ pub struct Config {
     pub dev_routes: HashSet<String>,
     pub exposed_secret: String,
     pub exposed_routes: HashSet<String>,
+    /// Requests per minute allowed from one client address. Unset disables the limit.
+    #[serde(default)]
+    pub rate_limit: Option<u64>,
+    /// Header the load balancer sets to the client address. Only set it when the balancer
+    /// overwrites the header; unset, clients are told apart by their socket address.
+    #[serde(default)]
+    pub ip_header: Option<String>,
     pub discord: Discord,
     pub cli_colors: bool,
     pub log_level: LogLevel,
     pub database: Database,
+    #[serde(default)]
+    pub cache: Cache,
+    #[serde(default)]
+    pub jobs: Jobs,
+    /// Enables the leaderboard of last attackers. Keep it off `dev_routes` and `exposed_routes`.
+    #[serde(default)]
+    pub killers_leaderboard: bool,
+    #[serde(default)]
+    pub feedback: Feedback,
     pub servers: Vec<Server>,
 }
 
@@ -36,6 +56,82 @@ pub struct Database {
     pub api_database: String,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Cache {
+    #[default]
+    Memory,
+    Redis {
+        url: String,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Jobs {
+    /// Department name to the current names of the jobs in it.
+    #[serde(default)]
+    pub departments: BTreeMap<String, Vec<String>>,
+    /// Former job name to the name it was renamed to.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl Jobs {
+    /// Returns the configured spelling of `department`, matched case-insensitively.
+    pub fn department(&self, department: &str) -> Option<&str> {
+        self.departments
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case(department))
+            .map(String::as_str)
+    }
+
+    /// Returns every job name stored for `department`, including former names.
+    pub fn department_jobs(&self, department: &str) -> Vec<&str> {
+        let Some(jobs) = self.departments.get(department) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<&str> = jobs.iter().map(String::as_str).collect();
+
+        for (alias, job) in &self.aliases {
+            if jobs.contains(job) {
+                names.push(alias);
+            }
+        }
+
+        names
+    }
+
+    /// Returns the departments `job` belongs to, resolving former names first.
+    pub fn departments_of(&self, job: &str) -> Vec<&str> {
+        let job = self.aliases.get(job).map(String::as_str).unwrap_or(job);
+
+        self.departments
+            .iter()
+            .filter(|(_, jobs)| jobs.iter().any(|name| name == job))
+            .map(|(department, _)| department.as_str())
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Feedback {
+    /// Feedback keys that are never served. A trailing `*` matches any key with that prefix.
+    #[serde(default)]
+    pub denylist: Vec<String>,
+}
+
+impl Feedback {
+    pub fn is_denied(&self, key: &str) -> bool {
+        self.denylist
+            .iter()
+            .any(|denied| match denied.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == denied,
+            })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Server {
     pub name: String,
@@ -56,3 +152,44 @@ pub enum Error {
     Io(#[from] std::io::Error),
     Toml(#[from] toml::de::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configs_without_rate_limit_or_cache_still_load() {
+        let config: Config = toml::from_str(
+            r#"
+            address = "127.0.0.1"
+            port = 3000
+            secret = ""
+            dev_secret = ""
+            dev_routes = []
+            exposed_secret = ""
+            exposed_routes = []
+            cli_colors = true
+            log_level = "normal"
+            servers = []
+
+            [discord]
+            token = ""
+            guild = 0
+            patreon_role = 0
+
+            [database]
+            user = "root"
+            password = ""
+            host = "127.0.0.1"
+            port = 3306
+            game_database = ""
+            api_database = ""
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.rate_limit, None);
+        assert_eq!(config.ip_header, None);
+        assert!(matches!(config.cache, Cache::Memory));
+    }
+}
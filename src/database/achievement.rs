@@ -0,0 +1,219 @@
+use chrono::NaiveDateTime;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
+
+use crate::cache::Cache;
+
+use super::error::Error;
+
+/// Players seen within this many days count as active when working out rarity.
+pub const ACTIVE_PLAYER_DAYS: u32 = 30;
+
+/// Number of active players holding the achievement `m.achievement_key`, binding the window in
+/// days.
+const ACTIVE_HOLDERS: &str = "(SELECT COUNT(*) FROM achievements h JOIN player p ON p.ckey = h.ckey WHERE h.achievement_key = m.achievement_key AND p.lastseen >= NOW() - INTERVAL ? DAY)";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AchievementRarity {
+    pub achievement_key: String,
+    pub achievement_version: u16,
+    pub achievement_type: Option<String>,
+    pub achievement_name: Option<String>,
+    pub achievement_description: Option<String>,
+    pub holders: i64,
+    /// Percentage of active players holding the achievement.
+    pub rarity: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AchievementScore {
+    pub ckey: String,
+    pub value: Option<i32>,
+    #[serde(with = "crate::serde::datetime")]
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AchievementUnlock {
+    pub ckey: String,
+    pub achievement_key: String,
+    pub achievement_type: Option<String>,
+    pub achievement_name: Option<String>,
+    pub value: Option<i32>,
+    #[serde(with = "crate::serde::datetime")]
+    pub timestamp: NaiveDateTime,
+}
+
+async fn count_active_players(
+    days: u32,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<i64, Error> {
+    let query = sqlx::query("SELECT COUNT(*) FROM player WHERE lastseen >= NOW() - INTERVAL ? DAY")
+        .bind(days);
+
+    Ok(connection.fetch_one(query).await?.try_get(0)?)
+}
+
+fn rarity(active_holders: i64, active_players: i64) -> Option<f64> {
+    if active_players == 0 {
+        return None;
+    }
+
+    Some(active_holders as f64 * 100.0 / active_players as f64)
+}
+
+pub async fn get_achievement_rarities(
+    active_days: u32,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<AchievementRarity>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let achievements = achievement_rarities(active_days, cache, &mut connection).await;
+
+    connection.close().await?;
+
+    achievements
+}
+
+/// Counting active holders walks every achievement row, so the table is cached per window rather
+/// than worked out on each request.
+pub(super) async fn achievement_rarities(
+    active_days: u32,
+    cache: &Cache,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<AchievementRarity>, Error> {
+    cache
+        .achievement_rarities
+        .get_or_try_load(active_days, || {
+            fetch_achievement_rarities(active_days, connection)
+        })
+        .await
+}
+
+async fn fetch_achievement_rarities(
+    active_days: u32,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<AchievementRarity>, Error> {
+    let active_players = count_active_players(active_days, connection).await?;
+
+    let sql = format!(
+        "SELECT m.achievement_key, m.achievement_version, m.achievement_type, m.achievement_name, m.achievement_description, (SELECT COUNT(*) FROM achievements a WHERE a.achievement_key = m.achievement_key) AS holders, {ACTIVE_HOLDERS} AS active_holders FROM achievement_metadata m ORDER BY holders DESC, m.achievement_key ASC"
+    );
+
+    let query = sqlx::query(&sql).bind(active_days);
+
+    let mut achievements = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            achievements.push(AchievementRarity {
+                achievement_key: row.try_get("achievement_key")?,
+                achievement_version: row.try_get("achievement_version")?,
+                achievement_type: row.try_get("achievement_type")?,
+                achievement_name: row.try_get("achievement_name")?,
+                achievement_description: row.try_get("achievement_description")?,
+                holders: row.try_get("holders")?,
+                rarity: rarity(row.try_get("active_holders")?, active_players),
+            });
+        }
+    }
+
+    Ok(achievements)
+}
+
+pub async fn get_achievement_leaderboard(
+    achievement_key: &str,
+    limit: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<AchievementScore>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT 1 FROM achievement_metadata WHERE achievement_key = ? AND achievement_type = 'score'",
+    )
+    .bind(achievement_key);
+
+    if connection.fetch_optional(query).await?.is_none() {
+        connection.close().await?;
+        return Err(Error::AchievementNotFound);
+    }
+
+    let query = sqlx::query(
+        "SELECT ckey, value, last_updated FROM achievements WHERE achievement_key = ? ORDER BY value DESC, last_updated ASC LIMIT ?",
+    )
+    .bind(achievement_key)
+    .bind(limit);
+
+    let mut scores = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            scores.push(AchievementScore {
+                ckey: row.try_get("ckey")?,
+                value: row.try_get("value")?,
+                timestamp: row.try_get("last_updated")?,
+            });
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(scores)
+}
+
+pub async fn get_recent_unlocks(
+    achievement_type: Option<&str>,
+    limit: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<AchievementUnlock>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let mut sql = "SELECT a.ckey, a.value, a.last_updated, m.achievement_key, m.achievement_type, m.achievement_name FROM achievements a JOIN achievement_metadata m ON a.achievement_key = m.achievement_key".to_string();
+
+    if achievement_type.is_some() {
+        sql.push_str(" WHERE m.achievement_type = ?");
+    }
+
+    sql.push_str(" ORDER BY a.last_updated DESC, a.ckey ASC LIMIT ?");
+
+    let mut query = sqlx::query(&sql);
+
+    if let Some(achievement_type) = achievement_type {
+        query = query.bind(achievement_type);
+    }
+
+    query = query.bind(limit);
+
+    let mut unlocks = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            unlocks.push(AchievementUnlock {
+                ckey: row.try_get("ckey")?,
+                achievement_key: row.try_get("achievement_key")?,
+                achievement_type: row.try_get("achievement_type")?,
+                achievement_name: row.try_get("achievement_name")?,
+                value: row.try_get("value")?,
+                timestamp: row.try_get("last_updated")?,
+            });
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(unlocks)
+}
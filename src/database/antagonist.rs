@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Executor as _, MySqlPool, Row as _};
+
+use crate::{ckey::Ckey, date::Date};
+
+use super::{error::Error, player_exists, range, Bucket};
+
+#[derive(Debug, Serialize)]
+pub struct AntagonistRole {
+    pub round_id: u32,
+    pub antagonist_name: Option<String>,
+    pub antagonist_type: Option<String>,
+    pub name: Option<String>,
+    pub objectives: usize,
+    pub objectives_completed: usize,
+    /// Whether every objective succeeded, `None` for roles without objectives.
+    pub success: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AntagonistTotal {
+    pub rounds: i64,
+    pub wins: i64,
+    /// Share of the rounds with objectives in which all of them succeeded.
+    pub win_rate: Option<f64>,
+    #[serde(skip)]
+    with_objectives: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerAntagonists {
+    pub antagonists: Vec<AntagonistRole>,
+    /// Keyed by `antagonist_type`, like the antagonist stats.
+    pub totals: BTreeMap<String, AntagonistTotal>,
+}
+
+/// Returns the entries of an `antagonists` feedback row in the order the game wrote them.
+pub(super) fn antagonist_entries(json: &Value) -> Vec<&Value> {
+    let Some(Value::Object(map)) = json.get("data") else {
+        return Vec::new();
+    };
+
+    let mut keys: Vec<_> = map.keys().collect();
+    keys.sort_by_key(|k| k.parse::<usize>().unwrap_or(usize::MAX));
+    keys.into_iter().map(|k| &map[k]).collect()
+}
+
+impl AntagonistRole {
+    pub(super) fn from_entry(round_id: u32, entry: &Value) -> Self {
+        let text = |field: &str| entry.get(field).and_then(Value::as_str).map(String::from);
+
+        let results: Vec<_> = entry
+            .get("objectives")
+            .and_then(Value::as_array)
+            .map(|objectives| {
+                objectives
+                    .iter()
+                    .map(|objective| objective.get("result").and_then(Value::as_str))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let objectives_completed = results
+            .iter()
+            .filter(|result| *result == &Some("SUCCESS"))
+            .count();
+
+        AntagonistRole {
+            round_id,
+            antagonist_name: text("antagonist_name"),
+            antagonist_type: text("antagonist_type"),
+            name: text("name"),
+            objectives: results.len(),
+            objectives_completed,
+            success: (!results.is_empty()).then_some(objectives_completed == results.len()),
+        }
+    }
+}
+
+/// Looks through the `antagonists` feedback of finished rounds the player connected to.
+pub async fn get_player_antagonists(
+    ckey: &Ckey,
+    pool: &MySqlPool,
+) -> Result<PlayerAntagonists, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT f.round_id, f.json FROM feedback f JOIN round r ON r.id = f.round_id WHERE f.key_name = 'antagonists' AND f.key_type = 'associative' AND r.end_datetime IS NOT NULL AND f.round_id IN (SELECT DISTINCT round_id FROM connection_log WHERE ckey = ?) ORDER BY f.round_id DESC",
+    )
+    .bind(ckey);
+
+    let mut antagonists = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let round_id: u32 = row.try_get("round_id")?;
+            let json: Value = row.try_get("json")?;
+
+            for entry in antagonist_entries(&json) {
+                let key = entry.get("key").and_then(Value::as_str).unwrap_or_default();
+
+                if Ckey::new(key).ok().as_ref() == Some(ckey) {
+                    antagonists.push(AntagonistRole::from_entry(round_id, entry));
+                }
+            }
+        }
+    }
+
+    if antagonists.is_empty() && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    let mut totals: BTreeMap<String, AntagonistTotal> = BTreeMap::new();
+
+    for antagonist in &antagonists {
+        let antagonist_type = antagonist
+            .antagonist_type
+            .clone()
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let total = totals.entry(antagonist_type).or_default();
+
+        total.rounds += 1;
+
+        if let Some(success) = antagonist.success {
+            total.with_objectives += 1;
+            total.wins += i64::from(success);
+            total.win_rate = Some(total.wins as f64 / total.with_objectives as f64);
+        }
+    }
+
+    Ok(PlayerAntagonists {
+        antagonists,
+        totals,
+    })
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AntagonistFrequency {
+    pub antagonists: i64,
+    pub rounds: i64,
+    /// Share of all objectives given to this antagonist type that succeeded.
+    pub objective_success_rate: Option<f64>,
+    /// Share of the antagonists with objectives that completed all of them.
+    pub win_rate: Option<f64>,
+    #[serde(skip)]
+    objectives: usize,
+    #[serde(skip)]
+    objectives_completed: usize,
+    #[serde(skip)]
+    with_objectives: i64,
+    #[serde(skip)]
+    wins: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameModeWeek {
+    #[serde(with = "crate::serde::date")]
+    pub week: NaiveDate,
+    pub rounds: i64,
+    pub dynamic_tiers: BTreeMap<String, i64>,
+    pub storytellers: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AntagonistStats {
+    pub rounds: i64,
+    /// Keyed by `antagonist_type`.
+    pub antagonist_types: BTreeMap<String, AntagonistFrequency>,
+    /// Averaged over the rounds that logged both their antagonists and readied players.
+    pub antagonists_per_round: Option<f64>,
+    pub readied_players_per_round: Option<f64>,
+    pub antagonists_per_readied_player: Option<f64>,
+    pub weeks: Vec<GameModeWeek>,
+}
+
+#[derive(Debug, Default)]
+struct RoundCounts {
+    week: Option<NaiveDate>,
+    antagonists: Option<usize>,
+    readied_players: Option<i64>,
+}
+
+/// The first entry of an associative feedback row, where single-entry keys keep their data.
+fn first_entry(json: &Value) -> Option<&Value> {
+    json.get("data")?.get("1")
+}
+
+/// Aggregates the `antagonists`, `dynamic_tier` and `storyteller` feedback of finished rounds
+/// initialized in `[from, to)`.
+pub async fn get_antagonist_stats(
+    from: Option<Date>,
+    to: Option<Date>,
+    pool: &MySqlPool,
+) -> Result<AntagonistStats, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT f.round_id, f.key_name, f.json, {} AS week FROM feedback f JOIN round r ON r.id = f.round_id WHERE f.key_name IN ('antagonists', 'dynamic_tier', 'storyteller') AND f.key_type = 'associative' AND r.end_datetime IS NOT NULL{} ORDER BY f.round_id ASC, f.id ASC",
+        Bucket::Week.start_of("r.initialize_datetime"),
+        range("r.initialize_datetime", from, to)
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for value in [from, to].into_iter().flatten() {
+        query = query.bind(value);
+    }
+
+    let mut rounds: HashMap<u32, RoundCounts> = HashMap::new();
+    let mut antagonist_types: BTreeMap<String, AntagonistFrequency> = BTreeMap::new();
+    let mut weeks: BTreeMap<NaiveDate, GameModeWeek> = BTreeMap::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let round_id: u32 = row.try_get("round_id")?;
+            let key_name: String = row.try_get("key_name")?;
+            let json: Value = row.try_get("json")?;
+            let date: NaiveDate = row.try_get("week")?;
+
+            let round = rounds.entry(round_id).or_default();
+
+            let week = weeks.entry(date).or_insert_with(|| GameModeWeek {
+                week: date,
+                rounds: 0,
+                dynamic_tiers: BTreeMap::new(),
+                storytellers: BTreeMap::new(),
+            });
+
+            if round.week.replace(date).is_none() {
+                week.rounds += 1;
+            }
+
+            match key_name.as_str() {
+                "antagonists" => {
+                    let entries = antagonist_entries(&json);
+                    let mut seen = Vec::new();
+
+                    round.antagonists = Some(entries.len());
+
+                    for entry in entries {
+                        let role = AntagonistRole::from_entry(round_id, entry);
+                        let antagonist_type = role
+                            .antagonist_type
+                            .unwrap_or_else(|| "Unknown".to_string());
+
+                        let frequency =
+                            antagonist_types.entry(antagonist_type.clone()).or_default();
+
+                        frequency.antagonists += 1;
+                        frequency.objectives += role.objectives;
+                        frequency.objectives_completed += role.objectives_completed;
+
+                        if !seen.contains(&antagonist_type) {
+                            frequency.rounds += 1;
+                            seen.push(antagonist_type);
+                        }
+
+                        if let Some(success) = role.success {
+                            frequency.with_objectives += 1;
+                            frequency.wins += i64::from(success);
+                        }
+                    }
+                }
+                "dynamic_tier" => {
+                    let entry = first_entry(&json);
+                    let text = |field| entry?.get(field)?.as_str();
+
+                    if let Some(tier) = text("tier") {
+                        *week.dynamic_tiers.entry(tier.to_string()).or_default() += 1;
+                    }
+
+                    round.readied_players =
+                        text("player_count").and_then(|count| count.parse().ok());
+                }
+                _ => {
+                    if let Some(name) = first_entry(&json)
+                        .and_then(|entry| entry.get("name"))
+                        .and_then(Value::as_str)
+                    {
+                        *week.storytellers.entry(name.to_string()).or_default() += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    connection.close().await?;
+
+    for frequency in antagonist_types.values_mut() {
+        if frequency.objectives > 0 {
+            frequency.objective_success_rate =
+                Some(frequency.objectives_completed as f64 / frequency.objectives as f64);
+        }
+
+        if frequency.with_objectives > 0 {
+            frequency.win_rate = Some(frequency.wins as f64 / frequency.with_objectives as f64);
+        }
+    }
+
+    let counted: Vec<(usize, i64)> = rounds
+        .values()
+        .filter_map(|round| Some((round.antagonists?, round.readied_players?)))
+        .collect();
+
+    let antagonists: usize = counted.iter().map(|(antagonists, _)| antagonists).sum();
+    let readied_players: i64 = counted.iter().map(|(_, readied)| readied).sum();
+
+    let per_round = |total: f64| (!counted.is_empty()).then(|| total / counted.len() as f64);
+
+    Ok(AntagonistStats {
+        rounds: rounds.len() as i64,
+        antagonist_types,
+        antagonists_per_round: per_round(antagonists as f64),
+        readied_players_per_round: per_round(readied_players as f64),
+        antagonists_per_readied_player: (readied_players > 0)
+            .then(|| antagonists as f64 / readied_players as f64),
+        weeks: weeks.into_values().collect(),
+    })
+}
@@ -1,11 +1,25 @@
+use std::net::SocketAddr;
+
 use chrono::NaiveDateTime;
+use rocket::{futures::StreamExt as _, FromForm, FromFormField};
 use serde::Serialize;
-use sqlx::{mysql::MySqlRow, Executor as _, MySqlPool, Row as _};
+use sqlx::{
+    mysql::{MySqlArguments, MySqlRow},
+    query::Query,
+    Executor as _, MySql, MySqlPool, Row as _,
+};
+
+use crate::{ckey::Ckey, config::Config, date::OptionalDate};
 
-use super::error::Error;
+use super::{
+    ban_edits::{parse_ban_edits, BanEdit},
+    error::Error,
+    round::server_address,
+};
 
 #[derive(Debug, Serialize)]
 pub struct Ban {
+    pub id: u32,
     #[serde(with = "crate::serde::datetime")]
     pub bantime: NaiveDateTime,
     pub round_id: Option<u32>,
@@ -16,6 +30,7 @@ pub struct Ban {
     pub ckey: Option<String>,
     pub a_ckey: String,
     pub edits: Option<String>,
+    pub edit_history: Vec<BanEdit>,
     #[serde(with = "crate::serde::opt_datetime")]
     pub unbanned_datetime: Option<NaiveDateTime>,
     pub unbanned_ckey: Option<String>,
@@ -25,7 +40,11 @@ impl TryFrom<MySqlRow> for Ban {
     type Error = sqlx::Error;
 
     fn try_from(value: MySqlRow) -> Result<Self, Self::Error> {
+        let edits: Option<String> = value.try_get("edits")?;
+        let edit_history = edits.as_deref().map(parse_ban_edits).unwrap_or_default();
+
         Ok(Self {
+            id: value.try_get("id")?,
             bantime: value.try_get("bantime")?,
             round_id: value.try_get("round_id")?,
             roles: value.try_get("roles")?,
@@ -33,7 +52,8 @@ impl TryFrom<MySqlRow> for Ban {
             reason: value.try_get("reason")?,
             ckey: value.try_get("ckey")?,
             a_ckey: value.try_get("a_ckey")?,
-            edits: value.try_get("edits")?,
+            edits,
+            edit_history,
             unbanned_datetime: value.try_get("unbanned_datetime")?,
             unbanned_ckey: value.try_get("unbanned_ckey")?,
         })
@@ -44,7 +64,7 @@ pub async fn get_ban_by_id(id: u32, pool: &MySqlPool) -> Result<Option<Ban>, Err
     let mut connection = pool.acquire().await?;
 
     let query = sqlx::query(
-    	"SELECT id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban WHERE id = ?"
+    	"SELECT id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban WHERE id = ? GROUP BY bantime, ckey, a_ckey"
     ).bind(id);
 
     let ban = match connection.fetch_optional(query).await? {
@@ -56,3 +76,301 @@ pub async fn get_ban_by_id(id: u32, pool: &MySqlPool) -> Result<Option<Ban>, Err
 
     Ok(ban)
 }
+
+const ACTIVE: &str =
+    "unbanned_datetime IS NULL AND (expiration_time IS NULL OR expiration_time > NOW())";
+const EXPIRED: &str = "unbanned_datetime IS NULL AND expiration_time <= NOW()";
+const UNBANNED: &str = "unbanned_datetime IS NOT NULL";
+
+#[derive(Debug, Clone, Copy, FromFormField)]
+pub enum BanStatus {
+    Active,
+    Expired,
+    Unbanned,
+}
+
+#[derive(Debug, FromForm)]
+pub struct BanFilters<'r> {
+    pub ckey: Option<Ckey>,
+    pub a_ckey: Option<Ckey>,
+    pub role: Option<&'r str>,
+    pub status: Option<BanStatus>,
+    /// Name of a server from the config, matched on its public connection address.
+    pub server: Option<&'r str>,
+    pub round_id: Option<u32>,
+    pub from: OptionalDate,
+    pub to: OptionalDate,
+    pub reason: Option<&'r str>,
+}
+
+/// Escapes the wildcards of a LIKE pattern so `value` only matches itself.
+pub(super) fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl BanFilters<'_> {
+    fn where_sql(&self, server: Option<SocketAddr>) -> String {
+        let mut sql = " WHERE 1 = 1".to_string();
+
+        if self.ckey.is_some() {
+            sql.push_str(" AND ckey = ?");
+        }
+
+        if self.a_ckey.is_some() {
+            sql.push_str(" AND a_ckey = ?");
+        }
+
+        if let Some(status) = self.status {
+            sql.push_str(" AND ");
+            sql.push_str(match status {
+                BanStatus::Active => ACTIVE,
+                BanStatus::Expired => EXPIRED,
+                BanStatus::Unbanned => UNBANNED,
+            });
+        }
+
+        if server.is_some() {
+            sql.push_str(" AND server_ip = INET_ATON(?) AND server_port = ?");
+        }
+
+        if self.round_id.is_some() {
+            sql.push_str(" AND round_id = ?");
+        }
+
+        if self.from.is_some() {
+            sql.push_str(" AND bantime >= ?");
+        }
+
+        if self.to.is_some() {
+            sql.push_str(" AND bantime < ?");
+        }
+
+        if self.reason.is_some() {
+            sql.push_str(" AND reason LIKE ?");
+        }
+
+        sql.push_str(" GROUP BY bantime, ckey, a_ckey");
+
+        if self.role.is_some() {
+            sql.push_str(" HAVING SUM(role = ?) > 0");
+        }
+
+        sql
+    }
+
+    fn bind<'q>(
+        &'q self,
+        server: Option<SocketAddr>,
+        mut query: Query<'q, MySql, MySqlArguments>,
+    ) -> Query<'q, MySql, MySqlArguments> {
+        if let Some(ckey) = &self.ckey {
+            query = query.bind(ckey);
+        }
+
+        if let Some(a_ckey) = &self.a_ckey {
+            query = query.bind(a_ckey);
+        }
+
+        if let Some(server) = server {
+            query = query.bind(server.ip().to_string()).bind(server.port());
+        }
+
+        if let Some(round_id) = self.round_id {
+            query = query.bind(round_id);
+        }
+
+        if let Some(from) = *self.from {
+            query = query.bind(from);
+        }
+
+        if let Some(to) = *self.to {
+            query = query.bind(to);
+        }
+
+        if let Some(reason) = self.reason {
+            query = query.bind(format!("%{}%", escape_like(reason)));
+        }
+
+        if let Some(role) = self.role {
+            query = query.bind(role);
+        }
+
+        query
+    }
+}
+
+pub async fn get_bans(
+    filters: &BanFilters<'_>,
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    config: &Config,
+    pool: &MySqlPool,
+) -> Result<(Vec<Ban>, i64), Error> {
+    let server = server_address(filters.server, config)?;
+
+    let fetch_size = fetch_size.unwrap_or(20);
+    let page = page.unwrap_or(1);
+    let offset = (page - 1) * fetch_size;
+
+    let mut connection = pool.acquire().await?;
+
+    let where_sql = filters.where_sql(server);
+
+    let sql = format!("SELECT COUNT(*) FROM (SELECT 1 FROM ban{where_sql}) AS bans");
+    let query = filters.bind(server, sqlx::query(&sql));
+
+    let total_count = connection.fetch_one(query).await?.try_get(0)?;
+
+    let sql = format!(
+        "SELECT MIN(id) AS id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban{where_sql} ORDER BY bantime DESC, id DESC LIMIT ? OFFSET ?"
+    );
+    let query = filters
+        .bind(server, sqlx::query(&sql))
+        .bind(fetch_size)
+        .bind(offset);
+
+    let mut bans = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            bans.push(row?.try_into()?);
+        }
+    }
+
+    connection.close().await?;
+
+    Ok((bans, total_count))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanIdentifier {
+    Ckey,
+    Ip,
+    Cid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BanMatch {
+    #[serde(flatten)]
+    pub ban: Ban,
+    pub matched: Vec<BanIdentifier>,
+}
+
+/// Returns the active bans that would stop the given connection from joining, or from playing
+/// `role` when one is given. Server bans apply to every role.
+pub async fn check_ban(
+    ckey: Option<&Ckey>,
+    ip: Option<&str>,
+    cid: Option<&str>,
+    role: Option<&str>,
+    pool: &MySqlPool,
+) -> Result<Vec<BanMatch>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let mut identifiers = Vec::new();
+
+    if ckey.is_some() {
+        identifiers.push("ckey = ?".to_string());
+    }
+
+    // Role bans only ever follow the ckey, so addresses and computer ids match server bans alone.
+    let mut connection_identifiers = Vec::new();
+
+    if ip.is_some() {
+        connection_identifiers.push("ip = INET_ATON(?)");
+    }
+
+    if cid.is_some() {
+        connection_identifiers.push("computerid = ?");
+    }
+
+    if !connection_identifiers.is_empty() {
+        identifiers.push(format!(
+            "(role = 'Server' AND ({}))",
+            connection_identifiers.join(" OR ")
+        ));
+    }
+
+    // Roles are filtered per ban rather than per row, so the roles listed are all of the ban's.
+    let roles = if role.is_some() {
+        "SUM(role IN ('Server', ?)) > 0"
+    } else {
+        "SUM(role = 'Server') > 0"
+    };
+
+    let sql = format!(
+        "SELECT MIN(id) AS id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, INET_NTOA(ip) AS readable_ip, computerid, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban WHERE ({}) AND {ACTIVE} GROUP BY bantime, ckey, a_ckey HAVING {roles} ORDER BY bantime DESC",
+        identifiers.join(" OR ")
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    if let Some(ckey) = ckey {
+        query = query.bind(ckey);
+    }
+
+    if let Some(ip) = ip {
+        query = query.bind(ip);
+    }
+
+    if let Some(cid) = cid {
+        query = query.bind(cid);
+    }
+
+    if let Some(role) = role {
+        query = query.bind(role);
+    }
+
+    let mut bans = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let banned_ip: Option<String> = row.try_get("readable_ip")?;
+            let banned_cid: Option<String> = row.try_get("computerid")?;
+            let ban: Ban = row.try_into()?;
+
+            let mut matched = Vec::new();
+
+            if ckey.is_some_and(|ckey| ban.ckey.as_deref() == Some(ckey.as_str())) {
+                matched.push(BanIdentifier::Ckey);
+            }
+
+            if ip.is_some() && banned_ip.as_deref() == ip {
+                matched.push(BanIdentifier::Ip);
+            }
+
+            if cid.is_some() && banned_cid.as_deref() == cid {
+                matched.push(BanIdentifier::Cid);
+            }
+
+            bans.push(BanMatch { ban, matched });
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(bans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(escape_like("grief"), "grief");
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("a_b"), "a\\_b");
+        assert_eq!(escape_like("c:\\"), "c:\\\\");
+    }
+}
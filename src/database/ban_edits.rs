@@ -0,0 +1,220 @@
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// Fields the game writes into `ban.edits`, in the order it writes them.
+static FIELD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|, )(Key|IP|CID|Applies to admins|Duration|Reason): ").unwrap());
+
+static TIMESTAMP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[?(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})\]?\s*").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanEditField {
+    Key,
+    Ip,
+    Cid,
+    AppliesToAdmins,
+    Duration,
+    Reason,
+}
+
+impl BanEditField {
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "Key" => Some(BanEditField::Key),
+            "IP" => Some(BanEditField::Ip),
+            "CID" => Some(BanEditField::Cid),
+            "Applies to admins" => Some(BanEditField::AppliesToAdmins),
+            "Duration" => Some(BanEditField::Duration),
+            "Reason" => Some(BanEditField::Reason),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct BanEdit {
+    pub editor: String,
+    #[serde(with = "crate::serde::opt_datetime")]
+    pub timestamp: Option<NaiveDateTime>,
+    pub field: BanEditField,
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// Parses the edit log the game appends to `ban.edits`, one
+/// `"<key> edited the following <changes><hr>"` entry per edit.
+///
+/// The game does not record when an edit was made, so `timestamp` is only set for entries that
+/// start with one. Entries that can't be parsed are skipped; the raw text stays on the ban.
+pub fn parse_ban_edits(edits: &str) -> Vec<BanEdit> {
+    let mut parsed = Vec::new();
+
+    for entry in edits.split("<hr>") {
+        let entry = entry.trim();
+
+        let (timestamp, entry) = match TIMESTAMP.captures(entry) {
+            Some(captures) => (
+                NaiveDateTime::parse_from_str(&captures[1], "%Y-%m-%d %H:%M:%S").ok(),
+                &entry[captures[0].len()..],
+            ),
+            None => (None, entry),
+        };
+
+        let Some((editor, changes)) = entry.split_once(" edited the following ") else {
+            continue;
+        };
+
+        let labels: Vec<_> = FIELD.captures_iter(changes).collect();
+
+        for (i, captures) in labels.iter().enumerate() {
+            let Some(field) = BanEditField::from_label(&captures[1]) else {
+                continue;
+            };
+
+            // The reason is written last and is free text, so it runs to the end of the entry.
+            let start = captures.get(0).unwrap().end();
+            let end = match field {
+                BanEditField::Reason => changes.len(),
+                _ => labels
+                    .get(i + 1)
+                    .map_or(changes.len(), |next| next.get(0).unwrap().start()),
+            };
+
+            let value = &changes[start..end];
+
+            let separator = match field {
+                BanEditField::Reason => "<br>to<br>",
+                _ => " to ",
+            };
+
+            let (old, new) = match value.split_once(separator) {
+                Some((old, new)) => (Some(old.trim().to_string()), new.trim().to_string()),
+                None => (None, value.trim().to_string()),
+            };
+
+            parsed.push(BanEdit {
+                editor: editor.trim().to_string(),
+                timestamp,
+                field,
+                old,
+                new,
+            });
+
+            if field == BanEditField::Reason {
+                break;
+            }
+        }
+    }
+
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(editor: &str, field: BanEditField, old: Option<&str>, new: &str) -> BanEdit {
+        BanEdit {
+            editor: editor.to_string(),
+            timestamp: None,
+            field,
+            old: old.map(str::to_string),
+            new: new.to_string(),
+        }
+    }
+
+    #[test]
+    fn single_reason_edit() {
+        let edits = "AdminGuy edited the following Reason: Griefing<br>to<br>Griefing, to the station, repeatedly<hr>";
+
+        assert_eq!(
+            parse_ban_edits(edits),
+            [edit(
+                "AdminGuy",
+                BanEditField::Reason,
+                Some("Griefing"),
+                "Griefing, to the station, repeatedly"
+            )]
+        );
+    }
+
+    #[test]
+    fn multiple_entries_and_fields() {
+        let edits = "AdminGuy edited the following Key: someplayer to otherplayer, IP: 10.0.0.1 to 10.0.0.2, CID: 1111111111 to 2222222222, Duration: 1440 MINUTES to 3 DAY<hr>Other Admin edited the following Applies to admins: 0 to 1<hr>";
+
+        assert_eq!(
+            parse_ban_edits(edits),
+            [
+                edit(
+                    "AdminGuy",
+                    BanEditField::Key,
+                    Some("someplayer"),
+                    "otherplayer"
+                ),
+                edit("AdminGuy", BanEditField::Ip, Some("10.0.0.1"), "10.0.0.2"),
+                edit(
+                    "AdminGuy",
+                    BanEditField::Cid,
+                    Some("1111111111"),
+                    "2222222222"
+                ),
+                edit(
+                    "AdminGuy",
+                    BanEditField::Duration,
+                    Some("1440 MINUTES"),
+                    "3 DAY"
+                ),
+                edit("Other Admin", BanEditField::AppliesToAdmins, Some("0"), "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reason_after_other_fields() {
+        let edits = "AdminGuy edited the following Duration: 60 MINUTES to 1 DAY, Reason: Powergaming, IP: none<br>to<br>Validhunting<hr>";
+
+        assert_eq!(
+            parse_ban_edits(edits),
+            [
+                edit(
+                    "AdminGuy",
+                    BanEditField::Duration,
+                    Some("60 MINUTES"),
+                    "1 DAY"
+                ),
+                edit(
+                    "AdminGuy",
+                    BanEditField::Reason,
+                    Some("Powergaming, IP: none"),
+                    "Validhunting"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamped_entry() {
+        let edits =
+            "[2024-01-02 10:30:00] AdminGuy edited the following Duration: 60 MINUTES to 2 HOUR<hr>";
+
+        let parsed = parse_ban_edits(edits);
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].editor, "AdminGuy");
+        assert_eq!(
+            parsed[0].timestamp,
+            NaiveDateTime::parse_from_str("2024-01-02 10:30:00", "%Y-%m-%d %H:%M:%S").ok()
+        );
+    }
+
+    #[test]
+    fn unparseable_entries_are_skipped() {
+        assert!(parse_ban_edits("").is_empty());
+        assert!(parse_ban_edits("<hr>").is_empty());
+        assert!(parse_ban_edits("Ban reactivated by AdminGuy<hr>").is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::NaiveDateTime;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{Executor as _, MySqlPool, Row as _};
+
+use crate::{cache::Cache, ckey::Ckey, config::Config};
+
+use super::{error::Error, get_round_ids, Crime, Death};
+
+#[derive(Debug, Serialize)]
+pub struct CharacterAppearance {
+    pub round_id: i32,
+    /// `None` when the player has hidden their ckey.
+    pub ckey: Option<String>,
+    pub job: String,
+    pub special: Option<String>,
+    pub latejoin: bool,
+    #[serde(with = "crate::serde::datetime")]
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Character {
+    pub name: String,
+    pub appearances: Vec<CharacterAppearance>,
+    pub deaths: Vec<Death>,
+    pub citations_sent: Vec<Crime>,
+    pub citations_received: Vec<Crime>,
+}
+
+/// Looks up every finished round a character name appeared in, optionally narrowed to one ckey.
+/// Hidden ckeys are left out of the appearances and can't be used to narrow the lookup.
+pub async fn get_character(
+    name: &str,
+    ckey: Option<&Ckey>,
+    pool: &MySqlPool,
+    config: &Config,
+    cache: &Cache,
+) -> Result<Character, Error> {
+    let live_rounds = get_round_ids(config, cache).await;
+
+    let mut connection = pool.acquire().await?;
+
+    let mut sql = format!(
+        "SELECT m.round_id, m.ckey, m.job, m.special, m.latejoin, m.timestamp, i.ckey AS hidden FROM {0}.manifest m JOIN {0}.round r ON r.id = m.round_id AND r.end_datetime IS NOT NULL LEFT JOIN {1}.hid_ckeys_autocomplete i ON i.ckey = m.ckey AND i.valid = 1 WHERE m.character_name = ?",
+        config.database.game_database, config.database.api_database
+    );
+
+    if !live_rounds.is_empty() {
+        sql.push_str(&format!(
+            " AND m.round_id NOT IN ({})",
+            vec!["?"; live_rounds.len()].join(", ")
+        ));
+    }
+
+    if ckey.is_some() {
+        sql.push_str(" AND m.ckey = ? AND i.ckey IS NULL");
+    }
+
+    sql.push_str(" ORDER BY m.timestamp DESC, m.id DESC");
+
+    let mut query = sqlx::query(&sql).bind(name);
+
+    for round_id in &live_rounds {
+        query = query.bind(round_id);
+    }
+
+    if let Some(ckey) = ckey {
+        query = query.bind(ckey);
+    }
+
+    let mut appearances = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let hidden: Option<String> = row.try_get("hidden")?;
+
+            appearances.push(CharacterAppearance {
+                round_id: row.try_get("round_id")?,
+                ckey: match hidden {
+                    Some(_) => None,
+                    None => Some(row.try_get("ckey")?),
+                },
+                job: row.try_get("job")?,
+                special: row.try_get("special")?,
+                latejoin: row.try_get("latejoin")?,
+                timestamp: row.try_get("timestamp")?,
+            });
+        }
+    }
+
+    if appearances.is_empty() {
+        connection.close().await?;
+        return Err(Error::CharacterNotFound);
+    }
+
+    // deaths and citations are only read from the finished rounds the character appeared in
+    let mut rounds: Vec<i32> = appearances
+        .iter()
+        .map(|appearance| appearance.round_id)
+        .collect();
+    rounds.sort_unstable();
+    rounds.dedup();
+
+    let placeholders = vec!["?"; rounds.len()].join(", ");
+
+    let sql = format!(
+        "SELECT name, job, pod, bruteloss, fireloss, oxyloss, toxloss, last_words, suicide, round_id, tod FROM death WHERE name = ? AND round_id IN ({placeholders}) ORDER BY tod DESC"
+    );
+
+    let mut query = sqlx::query(&sql).bind(name);
+
+    for round_id in &rounds {
+        query = query.bind(round_id);
+    }
+
+    let mut deaths = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            deaths.push(Death::try_from(row?)?);
+        }
+    }
+
+    let sql = format!(
+        "SELECT round_id, sender_ic, recipient, crime, crime_desc, fine, timestamp FROM citation WHERE (sender_ic = ? OR recipient = ?) AND round_id IN ({placeholders}) ORDER BY timestamp DESC"
+    );
+
+    let mut query = sqlx::query(&sql).bind(name).bind(name);
+
+    for round_id in &rounds {
+        query = query.bind(round_id);
+    }
+
+    let mut citations_sent = Vec::new();
+    let mut citations_received = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let citation = Crime::try_from(row?)?;
+
+            if citation.sender == name {
+                citations_sent.push(citation);
+            } else {
+                citations_received.push(citation);
+            }
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(Character {
+        name: name.to_string(),
+        appearances,
+        deaths,
+        citations_sent,
+        citations_received,
+    })
+}
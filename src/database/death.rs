@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{mysql::MySqlRow, Executor as _, MySqlPool, Row as _};
+
+use crate::{cache::Cache, ckey::Ckey, config::Config, date::Date};
+
+use super::{error::Error, get_round_ids, player_exists};
+
+#[derive(Debug, Serialize)]
+pub struct DamageBreakdown {
+    pub brute: u16,
+    pub brain: u16,
+    pub fire: u16,
+    pub oxy: u16,
+    pub tox: u16,
+    pub clone: u16,
+    pub stamina: u16,
+}
+
+impl DamageBreakdown {
+    /// Returns the damage type the character took the most of, if they took any.
+    pub fn dominant(&self) -> Option<&'static str> {
+        [
+            ("brute", self.brute),
+            ("brain", self.brain),
+            ("fire", self.fire),
+            ("oxy", self.oxy),
+            ("tox", self.tox),
+            ("clone", self.clone),
+            ("stamina", self.stamina),
+        ]
+        .into_iter()
+        .filter(|(_, amount)| *amount > 0)
+        .max_by_key(|(_, amount)| *amount)
+        .map(|(damage_type, _)| damage_type)
+    }
+}
+
+impl TryFrom<&MySqlRow> for DamageBreakdown {
+    type Error = sqlx::Error;
+
+    fn try_from(value: &MySqlRow) -> Result<Self, Self::Error> {
+        Ok(DamageBreakdown {
+            brute: value.try_get("bruteloss")?,
+            brain: value.try_get("brainloss")?,
+            fire: value.try_get("fireloss")?,
+            oxy: value.try_get("oxyloss")?,
+            tox: value.try_get("toxloss")?,
+            clone: value.try_get("cloneloss")?,
+            stamina: value.try_get("staminaloss")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerDeath {
+    pub name: String,
+    pub job: String,
+    pub special: Option<String>,
+    pub pod: String,
+    pub mapname: String,
+    /// IC name of the last attacker. Their ckey is only exposed through the killers leaderboard.
+    pub last_attacker: Option<String>,
+    pub damage: DamageBreakdown,
+    pub last_words: Option<String>,
+    pub suicide: bool,
+    pub round_id: u32,
+    #[serde(with = "crate::serde::datetime")]
+    pub tod: NaiveDateTime,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DeathStats {
+    pub deaths: i64,
+    pub suicides: i64,
+    pub by_job: BTreeMap<String, i64>,
+    /// Deaths keyed by the damage type dealt the most, `none` when no damage was recorded.
+    pub by_damage_type: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Killer {
+    pub ckey: String,
+    pub kills: i64,
+}
+
+const DEATH_COLUMNS: &str = "name, job, special, pod, mapname, laname, bruteloss, brainloss, fireloss, oxyloss, toxloss, cloneloss, staminaloss, last_words, suicide, round_id, tod";
+
+/// Deaths from rounds in progress are left out so they can't be used to metagame them. A round
+/// counts as in progress while it has no end time or while any server reports it as live.
+fn finished_rounds(live_rounds: &[i32]) -> String {
+    let mut sql =
+        " AND round_id IN (SELECT id FROM round WHERE end_datetime IS NOT NULL)".to_string();
+
+    if !live_rounds.is_empty() {
+        sql.push_str(&format!(
+            " AND round_id NOT IN ({})",
+            vec!["?"; live_rounds.len()].join(", ")
+        ));
+    }
+
+    sql
+}
+
+pub async fn get_player_deaths(
+    ckey: &Ckey,
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<(Vec<PlayerDeath>, i64), Error> {
+    let live_rounds = get_round_ids(config, cache).await;
+
+    let fetch_size = fetch_size.unwrap_or(20);
+    let page = page.unwrap_or(1);
+    let offset = (page - 1) * fetch_size;
+
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT COUNT(*) FROM death WHERE byondkey = ?{}",
+        finished_rounds(&live_rounds)
+    );
+
+    let mut query = sqlx::query_scalar(&sql).bind(ckey);
+
+    for round_id in &live_rounds {
+        query = query.bind(round_id);
+    }
+
+    let total_count: i64 = query.fetch_one(&mut *connection).await?;
+
+    let sql = format!(
+        "SELECT {DEATH_COLUMNS} FROM death WHERE byondkey = ?{} ORDER BY tod DESC LIMIT ? OFFSET ?",
+        finished_rounds(&live_rounds)
+    );
+
+    let mut query = sqlx::query(&sql).bind(ckey);
+
+    for round_id in &live_rounds {
+        query = query.bind(round_id);
+    }
+
+    query = query.bind(fetch_size).bind(offset);
+
+    let mut deaths = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            deaths.push(PlayerDeath {
+                name: row.try_get("name")?,
+                job: row.try_get("job")?,
+                special: row.try_get("special")?,
+                pod: row.try_get("pod")?,
+                mapname: row.try_get("mapname")?,
+                last_attacker: row.try_get("laname")?,
+                damage: DamageBreakdown::try_from(&row)?,
+                last_words: row.try_get("last_words")?,
+                suicide: row.try_get("suicide")?,
+                round_id: row.try_get("round_id")?,
+                tod: row.try_get("tod")?,
+            });
+        }
+    }
+
+    if total_count == 0 && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    Ok((deaths, total_count))
+}
+
+pub async fn get_player_death_stats(
+    ckey: &Ckey,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<DeathStats, Error> {
+    let live_rounds = get_round_ids(config, cache).await;
+
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT job, suicide, bruteloss, brainloss, fireloss, oxyloss, toxloss, cloneloss, staminaloss FROM death WHERE byondkey = ?{}",
+        finished_rounds(&live_rounds)
+    );
+
+    let mut query = sqlx::query(&sql).bind(ckey);
+
+    for round_id in &live_rounds {
+        query = query.bind(round_id);
+    }
+
+    let mut stats = DeathStats::default();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let suicide: bool = row.try_get("suicide")?;
+            let damage = DamageBreakdown::try_from(&row)?;
+
+            stats.deaths += 1;
+            stats.suicides += i64::from(suicide);
+            *stats.by_job.entry(row.try_get("job")?).or_default() += 1;
+            *stats
+                .by_damage_type
+                .entry(damage.dominant().unwrap_or("none").to_string())
+                .or_default() += 1;
+        }
+    }
+
+    if stats.deaths == 0 && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    Ok(stats)
+}
+
+/// Ranks players by how often they were the last attacker of someone else.
+pub async fn get_top_killers(
+    since: Option<Date>,
+    limit: u32,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<Killer>, Error> {
+    let live_rounds = get_round_ids(config, cache).await;
+
+    let mut connection = pool.acquire().await?;
+
+    let mut sql =
+        "SELECT lakey, COUNT(*) AS kills FROM death WHERE lakey IS NOT NULL AND lakey <> '' AND lakey <> byondkey AND suicide = 0"
+            .to_string();
+
+    sql.push_str(&finished_rounds(&live_rounds));
+
+    if since.is_some() {
+        sql.push_str(" AND tod >= ?");
+    }
+
+    sql.push_str(" GROUP BY lakey ORDER BY kills DESC, lakey ASC LIMIT ?");
+
+    let mut query = sqlx::query(&sql);
+
+    for round_id in &live_rounds {
+        query = query.bind(round_id);
+    }
+
+    if let Some(since) = since {
+        query = query.bind(since);
+    }
+
+    query = query.bind(limit);
+
+    let mut killers = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            killers.push(Killer {
+                ckey: row.try_get("lakey")?,
+                kills: row.try_get("kills")?,
+            });
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(killers)
+}
@@ -20,4 +20,14 @@ pub enum Error {
     TokenInvalid,
     #[error("Round not found")]
     RoundNotFound,
+    #[error("Achievement not found")]
+    AchievementNotFound,
+    #[error("Character not found")]
+    CharacterNotFound,
+    #[error("Server not found")]
+    ServerNotFound,
+    #[error("Feedback not found")]
+    FeedbackNotFound,
+    #[error("Invalid JSON path")]
+    InvalidJsonPath,
 }
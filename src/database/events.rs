@@ -4,9 +4,9 @@ use chrono::NaiveDateTime;
 use rocket::futures::StreamExt as _;
 use serde::Serialize;
 use serde_json::Value;
-use sqlx::{pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
+use sqlx::{mysql::MySqlRow, pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
 
-use crate::{byond::get_server_status, config::Config};
+use crate::{byond::get_server_status, cache::Cache, config::Config};
 
 use super::error::Error;
 
@@ -106,13 +106,34 @@ pub struct Death {
     pub tod: NaiveDateTime,
 }
 
+impl TryFrom<MySqlRow> for Death {
+    type Error = sqlx::Error;
+
+    fn try_from(value: MySqlRow) -> Result<Self, Self::Error> {
+        Ok(Death {
+            name: value.try_get("name")?,
+            job: value.try_get("job")?,
+            pod: value.try_get("pod")?,
+            bruteloss: value.try_get("bruteloss")?,
+            fireloss: value.try_get("fireloss")?,
+            oxyloss: value.try_get("oxyloss")?,
+            toxloss: value.try_get("toxloss")?,
+            last_words: value.try_get("last_words")?,
+            suicide: value.try_get("suicide")?,
+            round_id: value.try_get("round_id")?,
+            tod: value.try_get("tod")?,
+        })
+    }
+}
+
 pub async fn get_deaths(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<(Vec<Death>, i64), Error> {
-    let round_id = get_round_id(config).await?;
+    let round_id = get_round_id(config, cache).await?;
 
     let fetch_size = fetch_size.unwrap_or(20);
     let page = page.unwrap_or(1);
@@ -156,23 +177,7 @@ pub async fn get_deaths(
         let mut rows = connection.fetch(query);
 
         while let Some(row) = rows.next().await {
-            let death = row?;
-
-            let death = Death {
-                name: death.try_get("name")?,
-                job: death.try_get("job")?,
-                pod: death.try_get("pod")?,
-                bruteloss: death.try_get("bruteloss")?,
-                fireloss: death.try_get("fireloss")?,
-                oxyloss: death.try_get("oxyloss")?,
-                toxloss: death.try_get("toxloss")?,
-                last_words: death.try_get("last_words")?,
-                suicide: death.try_get("suicide")?,
-                round_id: death.try_get("round_id")?,
-                tod: death.try_get("tod")?,
-            };
-
-            deaths.push(death);
+            deaths.push(Death::try_from(row?)?);
         }
     }
 
@@ -193,13 +198,30 @@ pub struct Crime {
     pub timestamp: NaiveDateTime,
 }
 
+impl TryFrom<MySqlRow> for Crime {
+    type Error = sqlx::Error;
+
+    fn try_from(value: MySqlRow) -> Result<Self, Self::Error> {
+        Ok(Crime {
+            round_id: value.try_get("round_id")?,
+            sender: value.try_get("sender_ic")?,
+            recipient: value.try_get("recipient")?,
+            crime: value.try_get("crime")?,
+            crime_desc: value.try_get("crime_desc")?,
+            fine: value.try_get("fine")?,
+            timestamp: value.try_get("timestamp")?,
+        })
+    }
+}
+
 pub async fn get_citations(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<(Vec<Crime>, i64), Error> {
-    let round_id = get_round_id(config).await?;
+    let round_id = get_round_id(config, cache).await?;
 
     let fetch_size = fetch_size.unwrap_or(20);
     let page = page.unwrap_or(1);
@@ -244,19 +266,7 @@ pub async fn get_citations(
         let mut rows = connection.fetch(query);
 
         while let Some(row) = rows.next().await {
-            let citation = row?;
-
-            let citation = Crime {
-                round_id: citation.try_get("round_id")?,
-                sender: citation.try_get("sender_ic")?,
-                recipient: citation.try_get("recipient")?,
-                crime: citation.try_get("crime")?,
-                crime_desc: citation.try_get("crime_desc")?,
-                fine: citation.try_get("fine")?,
-                timestamp: citation.try_get("timestamp")?,
-            };
-
-            citations.push(citation);
+            citations.push(Crime::try_from(row?)?);
         }
     }
 
@@ -269,9 +279,10 @@ pub async fn get_crimes(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<(Vec<Crime>, i64), Error> {
-    let round_id = get_round_id(config).await?;
+    let round_id = get_round_id(config, cache).await?;
 
     let fetch_size = fetch_size.unwrap_or(20);
     let page = page.unwrap_or(1);
@@ -316,19 +327,7 @@ pub async fn get_crimes(
         let mut rows = connection.fetch(query);
 
         while let Some(row) = rows.next().await {
-            let crime = row?;
-
-            let crime = Crime {
-                round_id: crime.try_get("round_id")?,
-                sender: crime.try_get("sender_ic")?,
-                recipient: crime.try_get("recipient")?,
-                crime: crime.try_get("crime")?,
-                crime_desc: crime.try_get("crime_desc")?,
-                fine: crime.try_get("fine")?,
-                timestamp: crime.try_get("timestamp")?,
-            };
-
-            crimes.push(crime);
+            crimes.push(Crime::try_from(row?)?);
         }
     }
 
@@ -613,7 +612,7 @@ pub async fn get_antagonist_overview(
     Ok(antagonists)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Overview {
     pub round_id: u32,
     pub duration: i64,
@@ -632,11 +631,12 @@ pub struct Overview {
 pub async fn get_overview(
     limit: i32,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<Vec<Overview>, Error> {
     let mut connection = pool.acquire().await?;
 
-    let exclude_round = get_round_id(config).await?;
+    let exclude_round = get_round_id(config, cache).await?;
 
     let rounds = get_rounds_overview(limit, exclude_round, &mut connection).await?;
     let deaths = get_deaths_overview(limit, exclude_round, &mut connection).await?;
@@ -682,8 +682,8 @@ pub async fn get_overview(
     Ok(overview)
 }
 
-pub async fn get_round_id(config: &Config) -> Result<Option<i32>, Error> {
-    let status = get_server_status(config).await;
+pub async fn get_round_id(config: &Config, cache: &Cache) -> Result<Option<i32>, Error> {
+    let status = get_server_status(config, cache).await;
     let status = status.first();
 
     if let Some(status) = status {
@@ -694,3 +694,13 @@ pub async fn get_round_id(config: &Config) -> Result<Option<i32>, Error> {
 
     Ok(None)
 }
+
+/// Returns the round in progress on every server that responded.
+pub async fn get_round_ids(config: &Config, cache: &Cache) -> Vec<i32> {
+    get_server_status(config, cache)
+        .await
+        .iter()
+        .filter_map(|status| status.0.get("round_id")?.as_u64())
+        .map(|round_id| round_id as i32)
+        .collect()
+}
@@ -0,0 +1,296 @@
+use chrono::NaiveDateTime;
+use rocket::{futures::StreamExt as _, FromForm};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Executor as _, MySqlPool, Row as _};
+
+use crate::{cache::Cache, config::Config, date::OptionalDate};
+
+use super::{error::Error, get_round_ids};
+
+#[derive(Debug, Serialize)]
+pub struct FeedbackKey {
+    pub key_name: String,
+    pub key_type: String,
+    pub version: u8,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoundFeedback {
+    pub round_id: u32,
+    pub key_name: String,
+    pub key_type: String,
+    pub version: u8,
+    #[serde(with = "crate::serde::datetime")]
+    pub datetime: NaiveDateTime,
+    /// The `data` of the feedback, with associative entries turned into a list.
+    pub data: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedbackPoint {
+    pub round_id: u32,
+    #[serde(with = "crate::serde::datetime")]
+    pub datetime: NaiveDateTime,
+    pub value: Value,
+}
+
+/// Associative feedback is stored as an object keyed by `"1"`, `"2"`, ... in insertion order.
+fn parse_data(key_type: &str, json: &Value) -> Value {
+    let data = json.get("data").cloned().unwrap_or(Value::Null);
+
+    match (key_type, data) {
+        ("associative", Value::Object(map)) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by_key(|(key, _)| key.parse::<usize>().unwrap_or(usize::MAX));
+            Value::Array(entries.into_iter().map(|(_, value)| value).collect())
+        }
+        (_, data) => data,
+    }
+}
+
+/// Turns a dotted path such as `data.players.total` into a quoted JSON path, or `None` if a
+/// segment contains anything but letters, digits, underscores and spaces.
+fn json_path(path: &str) -> Option<String> {
+    let mut json_path = "$".to_string();
+
+    for segment in path.split('.') {
+        if segment.is_empty()
+            || !segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ' ')
+        {
+            return None;
+        }
+
+        json_path.push_str(&format!(".\"{segment}\""));
+    }
+
+    Some(json_path)
+}
+
+/// Refuses rounds still running on any server.
+async fn ensure_round_finished(round_id: u32, config: &Config, cache: &Cache) -> Result<(), Error> {
+    if get_round_ids(config, cache)
+        .await
+        .contains(&(round_id as i32))
+    {
+        return Err(Error::RoundNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn get_feedback_keys(
+    round_id: u32,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<FeedbackKey>, Error> {
+    ensure_round_finished(round_id, config, cache).await?;
+
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT key_name, key_type, version FROM feedback WHERE round_id = ? ORDER BY key_name ASC",
+    )
+    .bind(round_id);
+
+    let mut keys = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let key_name: String = row.try_get("key_name")?;
+
+            if config.feedback.is_denied(&key_name) {
+                continue;
+            }
+
+            keys.push(FeedbackKey {
+                key_name,
+                key_type: row.try_get("key_type")?,
+                version: row.try_get("version")?,
+            });
+        }
+    }
+
+    if keys.is_empty() {
+        let query = sqlx::query("SELECT 1 FROM round WHERE id = ?").bind(round_id);
+
+        if connection.fetch_optional(query).await?.is_none() {
+            connection.close().await?;
+            return Err(Error::RoundNotFound);
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(keys)
+}
+
+pub async fn get_round_feedback(
+    round_id: u32,
+    key_name: &str,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<RoundFeedback, Error> {
+    if config.feedback.is_denied(key_name) {
+        return Err(Error::FeedbackNotFound);
+    }
+
+    ensure_round_finished(round_id, config, cache).await?;
+
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT datetime, round_id, key_name, key_type, version, json FROM feedback WHERE round_id = ? AND key_name = ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(round_id)
+    .bind(key_name);
+
+    let Some(row) = connection.fetch_optional(query).await? else {
+        connection.close().await?;
+        return Err(Error::FeedbackNotFound);
+    };
+
+    let key_type: String = row.try_get("key_type")?;
+    let json: Value = row.try_get("json")?;
+
+    let feedback = RoundFeedback {
+        round_id: row.try_get("round_id")?,
+        key_name: row.try_get("key_name")?,
+        data: parse_data(&key_type, &json),
+        key_type,
+        version: row.try_get("version")?,
+        datetime: row.try_get("datetime")?,
+    };
+
+    connection.close().await?;
+
+    Ok(feedback)
+}
+
+#[derive(Debug, FromForm)]
+pub struct FeedbackSeries<'r> {
+    pub key: &'r str,
+    /// Dotted path into the feedback JSON, such as `data.players.total`.
+    pub path: &'r str,
+    pub from: OptionalDate,
+    pub to: OptionalDate,
+    pub limit: Option<u32>,
+}
+
+/// Extracts one value from a feedback key across finished rounds, oldest round first.
+pub async fn get_feedback_series(
+    series: &FeedbackSeries<'_>,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<FeedbackPoint>, Error> {
+    if config.feedback.is_denied(series.key) {
+        return Err(Error::FeedbackNotFound);
+    }
+
+    let Some(path) = json_path(series.path) else {
+        return Err(Error::InvalidJsonPath);
+    };
+
+    let limit = series.limit.unwrap_or(100).clamp(1, 1000);
+
+    let live_rounds = get_round_ids(config, cache).await;
+
+    let mut connection = pool.acquire().await?;
+
+    let mut sql = "SELECT round_id, datetime, CAST(JSON_EXTRACT(json, ?) AS CHAR) AS value FROM feedback WHERE key_name = ?".to_string();
+
+    if !live_rounds.is_empty() {
+        sql.push_str(&format!(
+            " AND round_id NOT IN ({})",
+            vec!["?"; live_rounds.len()].join(", ")
+        ));
+    }
+
+    if series.from.is_some() {
+        sql.push_str(" AND datetime >= ?");
+    }
+
+    if series.to.is_some() {
+        sql.push_str(" AND datetime < ?");
+    }
+
+    sql.push_str(" ORDER BY round_id DESC LIMIT ?");
+
+    let mut query = sqlx::query(&sql).bind(path).bind(series.key);
+
+    for round_id in live_rounds {
+        query = query.bind(round_id);
+    }
+
+    if let Some(from) = *series.from {
+        query = query.bind(from);
+    }
+
+    if let Some(to) = *series.to {
+        query = query.bind(to);
+    }
+
+    query = query.bind(limit);
+
+    let mut points = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let value: Option<String> = row.try_get("value")?;
+
+            let Some(value) = value else {
+                continue;
+            };
+
+            points.push(FeedbackPoint {
+                round_id: row.try_get("round_id")?,
+                datetime: row.try_get("datetime")?,
+                value: serde_json::from_str(&value)?,
+            });
+        }
+    }
+
+    connection.close().await?;
+
+    points.reverse();
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dotted_paths_are_quoted() {
+        assert_eq!(
+            json_path("data.players.total").as_deref(),
+            Some(r#"$."data"."players"."total""#)
+        );
+        assert_eq!(
+            json_path("data.1.tier").as_deref(),
+            Some(r#"$."data"."1"."tier""#)
+        );
+    }
+
+    #[test]
+    fn unsafe_paths_are_rejected() {
+        assert_eq!(json_path(""), None);
+        assert_eq!(json_path("data..total"), None);
+        assert_eq!(json_path("data.*"), None);
+        assert_eq!(json_path(r#"data"')"#), None);
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::NaiveDateTime;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
+
+use crate::ckey::Ckey;
+
+use super::error::Error;
+
+// filters 104.28.0.0/16 (cloudflare) subnet
+const EXCLUSION_SUBNET: &str = " AND (ip & INET_ATON('255.255.0.0')) <> INET_ATON('104.28.0.0')";
+
+/// Most connection rows read while expanding one level of the graph. A shared address can link
+/// thousands of ckeys, so the most recently seen ones are kept and the graph is marked truncated.
+const MAX_CONNECTIONS_PER_LEVEL: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Ckey,
+    Ip,
+    Cid,
+}
+
+impl NodeKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            NodeKind::Ckey => "ckey",
+            NodeKind::Ip => "ip",
+            NodeKind::Cid => "cid",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub value: String,
+    pub depth: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    #[serde(with = "crate::serde::opt_datetime")]
+    pub first_seen: Option<NaiveDateTime>,
+    #[serde(with = "crate::serde::opt_datetime")]
+    pub last_seen: Option<NaiveDateTime>,
+    pub connections: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub truncated: bool,
+}
+
+pub enum LookupStart<'a> {
+    Ckey(&'a Ckey),
+    Ip(&'a str),
+    Cid(&'a str),
+}
+
+struct Connection {
+    ckey: String,
+    ip: String,
+    cid: Option<String>,
+    first_seen: Option<NaiveDateTime>,
+    last_seen: Option<NaiveDateTime>,
+    connections: i64,
+}
+
+struct GraphBuilder {
+    nodes: Vec<GraphNode>,
+    ids: HashSet<String>,
+    edges: BTreeMap<(String, String), GraphEdge>,
+    max_nodes: usize,
+    truncated: bool,
+}
+
+impl GraphBuilder {
+    /// Adds a node unless it is already known, returning whether it was added.
+    fn add_node(&mut self, kind: NodeKind, value: &str, depth: u32) -> bool {
+        let id = format!("{}:{value}", kind.prefix());
+
+        if self.ids.contains(&id) {
+            return false;
+        }
+
+        if self.nodes.len() >= self.max_nodes {
+            self.truncated = true;
+            return false;
+        }
+
+        self.ids.insert(id.clone());
+        self.nodes.push(GraphNode {
+            id,
+            kind,
+            value: value.to_string(),
+            depth,
+        });
+
+        true
+    }
+
+    fn add_edge(&mut self, ckey: &str, kind: NodeKind, value: &str, connection: &Connection) {
+        let source = format!("ckey:{ckey}");
+        let target = format!("{}:{value}", kind.prefix());
+
+        if !self.ids.contains(&source) || !self.ids.contains(&target) {
+            return;
+        }
+
+        let edge = self
+            .edges
+            .entry((source.clone(), target.clone()))
+            .or_insert(GraphEdge {
+                source,
+                target,
+                first_seen: None,
+                last_seen: None,
+                connections: 0,
+            });
+
+        edge.first_seen = match (edge.first_seen, connection.first_seen) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        edge.last_seen = edge.last_seen.max(connection.last_seen);
+        edge.connections += connection.connections;
+    }
+}
+
+pub async fn lookup_graph(
+    start: LookupStart<'_>,
+    max_depth: u32,
+    max_nodes: usize,
+    pool: &MySqlPool,
+) -> Result<Graph, Error> {
+    let mut graph = GraphBuilder {
+        nodes: Vec::new(),
+        ids: HashSet::new(),
+        edges: BTreeMap::new(),
+        max_nodes,
+        truncated: false,
+    };
+
+    let mut frontier = match start {
+        LookupStart::Ckey(ckey) => vec![(NodeKind::Ckey, ckey.to_string())],
+        LookupStart::Ip(ip) => vec![(NodeKind::Ip, ip.to_string())],
+        LookupStart::Cid(cid) => vec![(NodeKind::Cid, cid.to_string())],
+    };
+
+    for (kind, value) in &frontier {
+        graph.add_node(*kind, value, 0);
+    }
+
+    let mut visited = HashSet::new();
+
+    let mut connection = pool.acquire().await?;
+
+    for depth in 0..max_depth {
+        if frontier.is_empty() {
+            break;
+        }
+
+        let (connections, truncated) =
+            fetch_connections(&frontier, MAX_CONNECTIONS_PER_LEVEL, &mut connection).await?;
+
+        graph.truncated |= truncated;
+
+        let mut next = Vec::new();
+
+        for connection in connections {
+            if !visited.insert((
+                connection.ckey.clone(),
+                connection.ip.clone(),
+                connection.cid.clone(),
+            )) {
+                continue;
+            }
+
+            let mut nodes = vec![
+                (NodeKind::Ckey, connection.ckey.clone()),
+                (NodeKind::Ip, connection.ip.clone()),
+            ];
+
+            if let Some(cid) = &connection.cid {
+                nodes.push((NodeKind::Cid, cid.clone()));
+            }
+
+            for (kind, value) in nodes {
+                if graph.add_node(kind, &value, depth + 1) {
+                    next.push((kind, value));
+                }
+            }
+
+            graph.add_edge(&connection.ckey, NodeKind::Ip, &connection.ip, &connection);
+
+            if let Some(cid) = &connection.cid {
+                graph.add_edge(&connection.ckey, NodeKind::Cid, cid, &connection);
+            }
+        }
+
+        frontier = next;
+    }
+
+    connection.close().await?;
+
+    Ok(Graph {
+        nodes: graph.nodes,
+        edges: graph.edges.into_values().collect(),
+        truncated: graph.truncated,
+    })
+}
+
+/// Returns up to `limit` connections touching the frontier, and whether there were more.
+async fn fetch_connections(
+    frontier: &[(NodeKind, String)],
+    limit: usize,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<(Vec<Connection>, bool), Error> {
+    let values = |kind| -> Vec<&str> {
+        frontier
+            .iter()
+            .filter(|(node_kind, _)| *node_kind == kind)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    };
+
+    let ckeys = values(NodeKind::Ckey);
+    let ips = values(NodeKind::Ip);
+    let cids = values(NodeKind::Cid);
+
+    let placeholders = |count: usize, placeholder: &str| vec![placeholder; count].join(", ");
+
+    let mut conditions = Vec::new();
+
+    if !ckeys.is_empty() {
+        conditions.push(format!("ckey IN ({})", placeholders(ckeys.len(), "?")));
+    }
+
+    if !ips.is_empty() {
+        conditions.push(format!(
+            "ip IN ({})",
+            placeholders(ips.len(), "INET_ATON(?)")
+        ));
+    }
+
+    if !cids.is_empty() {
+        conditions.push(format!("computerid IN ({})", placeholders(cids.len(), "?")));
+    }
+
+    let sql = format!(
+        "SELECT ckey, INET_NTOA(ip) AS readable_ip, computerid, MIN(datetime) AS first_seen, MAX(datetime) AS last_seen, COUNT(*) AS connections FROM connection_log WHERE ckey IS NOT NULL AND ({}){EXCLUSION_SUBNET} GROUP BY ckey, ip, computerid ORDER BY last_seen DESC LIMIT ?",
+        conditions.join(" OR ")
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for value in ckeys.into_iter().chain(ips).chain(cids) {
+        query = query.bind(value);
+    }
+
+    // one extra row tells us whether the level was cut short
+    query = query.bind(limit as u64 + 1);
+
+    let mut connections = Vec::new();
+
+    let mut rows = connection.fetch(query);
+
+    while let Some(row) = rows.next().await {
+        let row = row?;
+
+        connections.push(Connection {
+            ckey: row.try_get("ckey")?,
+            ip: row.try_get("readable_ip")?,
+            cid: row.try_get("computerid")?,
+            first_seen: row.try_get("first_seen")?,
+            last_seen: row.try_get("last_seen")?,
+            connections: row.try_get("connections")?,
+        });
+    }
+
+    let truncated = connections.len() > limit;
+    connections.truncate(limit);
+
+    Ok((connections, truncated))
+}
@@ -0,0 +1,170 @@
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{Executor as _, MySqlPool, Row as _};
+
+use crate::{config::Config, date::Date};
+
+use super::{error::Error, median, range, round::game_address};
+
+#[derive(Debug, Default, Serialize)]
+pub struct MapStats {
+    pub rounds: i64,
+    /// Minutes between round start and end.
+    pub average_duration: Option<f64>,
+    pub median_duration: Option<i64>,
+    pub average_peak_population: Option<f64>,
+    pub deaths_per_round: f64,
+    pub shuttles: BTreeMap<String, i64>,
+    pub end_states: BTreeMap<String, i64>,
+    pub average_dynamic_tier: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MapSummary {
+    pub map_name: String,
+    #[serde(flatten)]
+    pub stats: MapStats,
+    /// The same numbers for each configured server the map was played on.
+    pub servers: BTreeMap<String, MapStats>,
+}
+
+#[derive(Debug, Default)]
+struct MapRounds {
+    durations: Vec<i64>,
+    peak_populations: Vec<i64>,
+    dynamic_tiers: Vec<i64>,
+    deaths: i64,
+    stats: MapStats,
+}
+
+struct MapRound {
+    duration: Option<i64>,
+    peak_population: Option<i64>,
+    dynamic_tier: Option<i64>,
+    deaths: i64,
+    shuttle_name: String,
+    end_state: String,
+}
+
+fn average(values: &[i64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<i64>() as f64 / values.len() as f64)
+}
+
+impl MapRounds {
+    fn add(&mut self, round: &MapRound) {
+        self.stats.rounds += 1;
+        self.deaths += round.deaths;
+        self.durations.extend(round.duration);
+        self.peak_populations.extend(round.peak_population);
+        self.dynamic_tiers.extend(round.dynamic_tier);
+
+        *self
+            .stats
+            .shuttles
+            .entry(round.shuttle_name.clone())
+            .or_default() += 1;
+        *self
+            .stats
+            .end_states
+            .entry(round.end_state.clone())
+            .or_default() += 1;
+    }
+
+    fn finish(mut self) -> MapStats {
+        self.durations.sort_unstable();
+
+        MapStats {
+            average_duration: average(&self.durations),
+            median_duration: median(&self.durations),
+            average_peak_population: average(&self.peak_populations),
+            deaths_per_round: self.deaths as f64 / self.stats.rounds as f64,
+            average_dynamic_tier: average(&self.dynamic_tiers),
+            ..self.stats
+        }
+    }
+}
+
+/// Aggregates finished rounds by map, optionally limited to rounds initialized in `[from, to)`.
+pub async fn get_map_stats(
+    from: Option<Date>,
+    to: Option<Date>,
+    config: &Config,
+    pool: &MySqlPool,
+) -> Result<Vec<MapSummary>, Error> {
+    let servers: Vec<(SocketAddr, &str)> = config
+        .servers
+        .iter()
+        .filter_map(|server| Some((game_address(server)?, server.name.as_str())))
+        .collect();
+
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT IFNULL(map_name, 'Unknown') AS map_name, INET_NTOA(server_ip) AS server_ip, server_port, TIMESTAMPDIFF(MINUTE, start_datetime, end_datetime) AS duration, IFNULL(shuttle_name, 'None') AS shuttle_name, IFNULL(end_state, 'Unknown') AS end_state,
+            (SELECT CAST(MAX(playercount) AS SIGNED) FROM legacy_population p WHERE p.round_id = round.id) AS peak_population,
+            (SELECT COUNT(*) FROM death d WHERE d.round_id = round.id) AS deaths,
+            (SELECT CAST(JSON_UNQUOTE(JSON_EXTRACT(f.json, '$.data.\"1\".tier')) AS SIGNED) FROM feedback f WHERE f.round_id = round.id AND f.key_name = 'dynamic_tier' ORDER BY f.id DESC LIMIT 1) AS dynamic_tier
+        FROM round WHERE end_datetime IS NOT NULL{}",
+        range("initialize_datetime", from, to)
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for value in [from, to].into_iter().flatten() {
+        query = query.bind(value);
+    }
+
+    let mut maps: BTreeMap<String, (MapRounds, BTreeMap<String, MapRounds>)> = BTreeMap::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let round = MapRound {
+                duration: row.try_get("duration")?,
+                peak_population: row.try_get("peak_population")?,
+                dynamic_tier: row.try_get("dynamic_tier")?,
+                deaths: row.try_get("deaths")?,
+                shuttle_name: row.try_get("shuttle_name")?,
+                end_state: row.try_get("end_state")?,
+            };
+
+            let (map, map_servers) = maps.entry(row.try_get("map_name")?).or_default();
+
+            map.add(&round);
+
+            let server_ip: Option<String> = row.try_get("server_ip")?;
+            let server_port: u16 = row.try_get("server_port")?;
+
+            let address = server_ip
+                .and_then(|ip| ip.parse().ok())
+                .map(|ip| SocketAddr::new(ip, server_port));
+
+            if let Some((_, name)) = servers.iter().find(|(addr, _)| Some(*addr) == address) {
+                map_servers.entry(name.to_string()).or_default().add(&round);
+            }
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(maps
+        .into_iter()
+        .map(|(map_name, (map, map_servers))| MapSummary {
+            map_name,
+            stats: map.finish(),
+            servers: map_servers
+                .into_iter()
+                .map(|(name, server)| (name, server.finish()))
+                .collect(),
+        })
+        .collect())
+}
@@ -1,16 +1,37 @@
+mod achievement;
+mod antagonist;
 mod ban;
+mod ban_edits;
+mod character;
+mod death;
 pub mod error;
 mod events;
+mod feedback;
+mod lookup;
+mod map;
+mod moderation;
 mod player;
 mod round;
 mod state;
 mod test_merges;
+mod ticket;
+mod timeline;
 mod verify;
 
+pub use achievement::*;
+pub use antagonist::*;
 pub use ban::*;
+pub use character::*;
+pub use death::*;
 pub use events::*;
+pub use feedback::*;
+pub use lookup::*;
+pub use map::*;
+pub use moderation::*;
 pub use player::*;
 pub use round::*;
 pub use state::Database;
 pub use test_merges::*;
+pub use ticket::*;
+pub use timeline::*;
 pub use verify::*;
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
+
+use crate::date::Date;
+
+use super::{error::Error, ticket::RESPONSE_ACTIONS, Bucket};
+
+const BAN_DURATION: &str = "CASE
+    WHEN expiration_time IS NULL THEN 'permanent'
+    WHEN TIMESTAMPDIFF(MINUTE, bantime, expiration_time) <= 1440 THEN 'up_to_day'
+    WHEN TIMESTAMPDIFF(MINUTE, bantime, expiration_time) <= 10080 THEN 'up_to_week'
+    WHEN TIMESTAMPDIFF(MINUTE, bantime, expiration_time) <= 43200 THEN 'up_to_month'
+    ELSE 'over_month'
+END";
+
+#[derive(Debug, Serialize)]
+pub struct TicketHandling {
+    pub admin: String,
+    pub handled: i64,
+    /// Median seconds between a ticket being opened and this admin's first response.
+    pub median_first_response: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ModerationWeek {
+    #[serde(with = "crate::serde::date")]
+    pub week: NaiveDate,
+    pub bans_by_admin: BTreeMap<String, i64>,
+    pub bans_by_role: BTreeMap<String, i64>,
+    pub bans_by_duration: BTreeMap<String, i64>,
+    pub notes_by_admin: BTreeMap<String, i64>,
+    pub notes_by_severity: BTreeMap<String, i64>,
+    pub tickets: Vec<TicketHandling>,
+}
+
+pub async fn get_moderation_stats(
+    from: Option<Date>,
+    to: Option<Date>,
+    pool: &MySqlPool,
+) -> Result<Vec<ModerationWeek>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let ban_week = Bucket::Week.start_of("bantime");
+    let bans = range("bantime", from, to);
+    let note_week = Bucket::Week.start_of("timestamp");
+    let notes = range("timestamp", from, to);
+
+    let counts: [(String, Counts); 5] = [
+        (
+            format!("SELECT {ban_week} AS week, a_ckey AS name, COUNT(DISTINCT bantime, IFNULL(ckey, '')) AS count FROM ban WHERE 1 = 1{bans} GROUP BY week, name"),
+            |week| &mut week.bans_by_admin,
+        ),
+        (
+            format!("SELECT {ban_week} AS week, IFNULL(role, 'Unknown') AS name, COUNT(*) AS count FROM ban WHERE 1 = 1{bans} GROUP BY week, name"),
+            |week| &mut week.bans_by_role,
+        ),
+        (
+            format!("SELECT {ban_week} AS week, {BAN_DURATION} AS name, COUNT(DISTINCT bantime, IFNULL(ckey, ''), IFNULL(a_ckey, '')) AS count FROM ban WHERE 1 = 1{bans} GROUP BY week, name"),
+            |week| &mut week.bans_by_duration,
+        ),
+        (
+            format!("SELECT {note_week} AS week, adminckey AS name, COUNT(*) AS count FROM messages WHERE type = 'note' AND deleted = 0{notes} GROUP BY week, name"),
+            |week| &mut week.notes_by_admin,
+        ),
+        (
+            format!("SELECT {note_week} AS week, IFNULL(severity, 'none') AS name, COUNT(*) AS count FROM messages WHERE type = 'note' AND deleted = 0{notes} GROUP BY week, name"),
+            |week| &mut week.notes_by_severity,
+        ),
+    ];
+
+    let mut weeks = BTreeMap::new();
+
+    for (sql, counts) in counts {
+        for (date, name, count) in fetch_counts(&sql, from, to, &mut connection).await? {
+            counts(week(&mut weeks, date)).insert(name, count);
+        }
+    }
+
+    let mut responses: BTreeMap<(NaiveDate, String), Vec<i64>> = BTreeMap::new();
+
+    for (date, admin, seconds) in fetch_first_responses(from, to, &mut connection).await? {
+        responses.entry((date, admin)).or_default().push(seconds);
+    }
+
+    for ((date, admin), mut seconds) in responses {
+        seconds.sort_unstable();
+
+        week(&mut weeks, date).tickets.push(TicketHandling {
+            admin,
+            handled: seconds.len() as i64,
+            median_first_response: median(&seconds),
+        });
+    }
+
+    connection.close().await?;
+
+    Ok(weeks.into_values().collect())
+}
+
+type Counts = fn(&mut ModerationWeek) -> &mut BTreeMap<String, i64>;
+
+fn week(weeks: &mut BTreeMap<NaiveDate, ModerationWeek>, date: NaiveDate) -> &mut ModerationWeek {
+    weeks.entry(date).or_insert_with(|| ModerationWeek {
+        week: date,
+        ..Default::default()
+    })
+}
+
+pub(super) fn range(column: &str, from: Option<Date>, to: Option<Date>) -> String {
+    let mut sql = String::new();
+
+    if from.is_some() {
+        sql.push_str(&format!(" AND {column} >= ?"));
+    }
+
+    if to.is_some() {
+        sql.push_str(&format!(" AND {column} < ?"));
+    }
+
+    sql
+}
+
+async fn fetch_counts(
+    sql: &str,
+    from: Option<Date>,
+    to: Option<Date>,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<(NaiveDate, String, i64)>, Error> {
+    let mut query = sqlx::query(sql);
+
+    for value in [from, to].into_iter().flatten() {
+        query = query.bind(value);
+    }
+
+    let mut counts = Vec::new();
+
+    let mut rows = connection.fetch(query);
+
+    while let Some(row) = rows.next().await {
+        let row = row?;
+
+        counts.push((
+            row.try_get("week")?,
+            row.try_get("name")?,
+            row.try_get("count")?,
+        ));
+    }
+
+    Ok(counts)
+}
+
+/// Returns the week, responding admin and seconds to first response of every ticket a player
+/// opened in the range and staff answered.
+async fn fetch_first_responses(
+    from: Option<Date>,
+    to: Option<Date>,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<(NaiveDate, String, i64)>, Error> {
+    let response = format!(
+        "FROM ticket r WHERE r.round_id = o.round_id AND r.ticket = o.ticket AND r.sender <> o.sender AND r.action IN {RESPONSE_ACTIONS} ORDER BY r.timestamp ASC, r.id ASC LIMIT 1"
+    );
+
+    let sql = format!(
+        "SELECT {} AS week, o.timestamp AS opened, (SELECT r.sender {response}) AS admin, (SELECT r.timestamp {response}) AS responded FROM ticket o WHERE o.action = 'Ticket Opened' AND o.recipient IS NULL{}",
+        Bucket::Week.start_of("o.timestamp"),
+        range("o.timestamp", from, to)
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for value in [from, to].into_iter().flatten() {
+        query = query.bind(value);
+    }
+
+    let mut responses = Vec::new();
+
+    let mut rows = connection.fetch(query);
+
+    while let Some(row) = rows.next().await {
+        let row = row?;
+
+        let admin: Option<String> = row.try_get("admin")?;
+        let responded: Option<NaiveDateTime> = row.try_get("responded")?;
+
+        let (Some(admin), Some(responded)) = (admin, responded) else {
+            continue;
+        };
+
+        let opened: NaiveDateTime = row.try_get("opened")?;
+
+        responses.push((
+            row.try_get("week")?,
+            admin,
+            (responded - opened).num_seconds(),
+        ));
+    }
+
+    Ok(responses)
+}
+
+pub(super) fn median(sorted: &[i64]) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let middle = sorted.len() / 2;
+
+    if sorted.len().is_multiple_of(2) {
+        Some((sorted[middle - 1] + sorted[middle]) / 2)
+    } else {
+        Some(sorted[middle])
+    }
+}
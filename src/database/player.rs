@@ -1,12 +1,22 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
 use chrono::{NaiveDate, NaiveDateTime};
 use const_format::concatcp;
-use rocket::futures::StreamExt as _;
+use rocket::{futures::StreamExt as _, FromFormField};
 use serde::Serialize;
 use sqlx::{pool::PoolConnection, Executor as _, FromRow, MySql, MySqlPool, Row as _};
 
-use crate::config::Config;
+use crate::{
+    cache::Cache,
+    ckey::Ckey,
+    config::{self, Config},
+    date::Date,
+};
 
-use super::{error::Error, Ban};
+use super::{achievement::achievement_rarities, error::Error, Ban, ACTIVE_PLAYER_DAYS};
 
 #[derive(Debug, Serialize)]
 pub struct Player {
@@ -22,13 +32,24 @@ pub struct Player {
     pub byond_age: Option<NaiveDate>,
 }
 
-pub async fn get_player(ckey: &str, pool: &MySqlPool) -> Result<Player, Error> {
+pub async fn get_player(ckey: &Ckey, pool: &MySqlPool) -> Result<Player, Error> {
     let mut connection = pool.acquire().await?;
 
+    let player = player_by_ckey(ckey, &mut connection).await;
+
+    connection.close().await?;
+
+    player
+}
+
+pub async fn player_by_ckey(
+    ckey: &Ckey,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Player, Error> {
     let query = sqlx::query(
-        "SELECT ckey, byond_key, firstseen, firstseen_round_id, lastseen, lastseen_round_id, INET_NTOA(ip), computerid, accountjoindate FROM player WHERE LOWER(ckey) = ?"
+        "SELECT ckey, byond_key, firstseen, firstseen_round_id, lastseen, lastseen_round_id, INET_NTOA(ip), computerid, accountjoindate FROM player WHERE ckey = ?"
     )
-    .bind(ckey.to_lowercase());
+    .bind(ckey);
 
     let Ok(row) = connection.fetch_one(query).await else {
         return Err(Error::PlayerNotFound);
@@ -44,24 +65,27 @@ pub async fn get_player(ckey: &str, pool: &MySqlPool) -> Result<Player, Error> {
         byond_age: row.try_get("accountjoindate")?,
     };
 
-    connection.close().await?;
-
     Ok(player)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct JobRoletime {
     ckey: String,
     minutes: u32,
 }
 
-pub async fn get_top_roletime(job: &str, pool: &MySqlPool) -> Result<Vec<JobRoletime>, Error> {
+pub async fn get_top_roletime(
+    job: &str,
+    limit: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<JobRoletime>, Error> {
     let mut connection = pool.acquire().await?;
 
     let query = sqlx::query(
-        "SELECT ckey, minutes FROM role_time WHERE LOWER(job) = ? ORDER BY minutes DESC LIMIT 15",
+        "SELECT ckey, minutes FROM role_time WHERE LOWER(job) = ? ORDER BY minutes DESC LIMIT ?",
     )
-    .bind(job.to_lowercase());
+    .bind(job.to_lowercase())
+    .bind(limit);
 
     let mut roletimes = Vec::new();
 
@@ -91,13 +115,28 @@ pub struct PlayerRoletime {
     minutes: u32,
 }
 
-pub async fn get_roletime(ckey: &str, pool: &MySqlPool) -> Result<Vec<PlayerRoletime>, Error> {
+pub async fn get_roletime(ckey: &Ckey, pool: &MySqlPool) -> Result<Vec<PlayerRoletime>, Error> {
     let mut connection = pool.acquire().await?;
 
-    let query = sqlx::query(
-        "SELECT job, minutes FROM role_time WHERE LOWER(ckey) = ? ORDER BY minutes DESC",
-    )
-    .bind(ckey.to_lowercase());
+    let roletimes = roletime_by_ckey(ckey, &mut connection).await?;
+
+    if roletimes.is_empty() && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    Ok(roletimes)
+}
+
+pub async fn roletime_by_ckey(
+    ckey: &Ckey,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<PlayerRoletime>, Error> {
+    let query =
+        sqlx::query("SELECT job, minutes FROM role_time WHERE ckey = ? ORDER BY minutes DESC")
+            .bind(ckey);
 
     let mut roletimes = Vec::new();
 
@@ -116,14 +155,216 @@ pub async fn get_roletime(ckey: &str, pool: &MySqlPool) -> Result<Vec<PlayerRole
         }
     }
 
-    if roletimes.is_empty() && !player_exists(ckey, &mut connection).await {
+    Ok(roletimes)
+}
+
+pub async fn get_top_department_roletime(
+    jobs: &[&str],
+    limit: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<JobRoletime>, Error> {
+    if jobs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT ckey, CAST(SUM(minutes) AS UNSIGNED) AS minutes FROM role_time WHERE job IN ({}) GROUP BY ckey ORDER BY minutes DESC LIMIT ?",
+        vec!["?"; jobs.len()].join(", ")
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for job in jobs {
+        query = query.bind(job);
+    }
+
+    query = query.bind(limit);
+
+    let mut roletimes = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let roletime = JobRoletime {
+                ckey: row.try_get("ckey")?,
+                minutes: row.try_get("minutes")?,
+            };
+
+            roletimes.push(roletime);
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(roletimes)
+}
+
+#[derive(Debug, Serialize)]
+pub struct DepartmentRoletime {
+    department: String,
+    minutes: u64,
+}
+
+pub async fn get_department_roletime(
+    ckey: &Ckey,
+    jobs: &config::Jobs,
+    pool: &MySqlPool,
+) -> Result<Vec<DepartmentRoletime>, Error> {
+    let roletimes = get_roletime(ckey, pool).await?;
+
+    let mut minutes: BTreeMap<&str, u64> = BTreeMap::new();
+
+    for roletime in &roletimes {
+        for department in jobs.departments_of(&roletime.job) {
+            *minutes.entry(department).or_default() += u64::from(roletime.minutes);
+        }
+    }
+
+    let mut departments: Vec<DepartmentRoletime> = minutes
+        .into_iter()
+        .map(|(department, minutes)| DepartmentRoletime {
+            department: department.to_string(),
+            minutes,
+        })
+        .collect();
+
+    departments.sort_by_key(|department| Reverse(department.minutes));
+
+    Ok(departments)
+}
+
+pub async fn get_top_roletime_since(
+    jobs: &[&str],
+    since: Date,
+    limit: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<JobRoletime>, Error> {
+    if jobs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut connection = pool.acquire().await?;
+
+    let sql = format!(
+        "SELECT ckey, CAST(GREATEST(SUM(delta), 0) AS UNSIGNED) AS minutes FROM role_time_log WHERE job IN ({}) AND datetime >= ? GROUP BY ckey ORDER BY minutes DESC LIMIT ?",
+        vec!["?"; jobs.len()].join(", ")
+    );
+
+    let mut query = sqlx::query(&sql);
+
+    for job in jobs {
+        query = query.bind(job);
+    }
+
+    query = query.bind(since).bind(limit);
+
+    let mut roletimes = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let roletime = JobRoletime {
+                ckey: row.try_get("ckey")?,
+                minutes: row.try_get("minutes")?,
+            };
+
+            roletimes.push(roletime);
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(roletimes)
+}
+
+#[derive(Debug, Clone, Copy, FromFormField)]
+pub enum Bucket {
+    Day,
+    Week,
+    Month,
+}
+
+impl Bucket {
+    /// SQL expression for the first day of the bucket `column` falls in.
+    pub fn start_of(self, column: &str) -> String {
+        match self {
+            Bucket::Day => format!("DATE({column})"),
+            Bucket::Week => format!("DATE({column} - INTERVAL WEEKDAY({column}) DAY)"),
+            Bucket::Month => format!("DATE(DATE_FORMAT({column}, '%Y-%m-01'))"),
+        }
+    }
+}
+
+pub async fn get_roletime_history(
+    ckey: &Ckey,
+    job: Option<&str>,
+    from: Option<Date>,
+    to: Option<Date>,
+    bucket: Bucket,
+    pool: &MySqlPool,
+) -> Result<Vec<(String, i64)>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let mut sql = format!(
+        "SELECT {} AS bucket, CAST(SUM(delta) AS SIGNED) AS minutes FROM role_time_log WHERE ckey = ?",
+        bucket.start_of("datetime")
+    );
+
+    if job.is_some() {
+        sql.push_str(" AND job = ?");
+    }
+
+    if from.is_some() {
+        sql.push_str(" AND datetime >= ?");
+    }
+
+    if to.is_some() {
+        sql.push_str(" AND datetime < ?");
+    }
+
+    sql.push_str(" GROUP BY bucket ORDER BY bucket ASC");
+
+    let mut query = sqlx::query(&sql).bind(ckey);
+
+    if let Some(job) = job {
+        query = query.bind(job);
+    }
+
+    for date in [from, to].into_iter().flatten() {
+        query = query.bind(date);
+    }
+
+    let mut history = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let bucket: NaiveDate = row.try_get("bucket")?;
+            let bucket = bucket.format("%Y-%m-%d").to_string();
+
+            history.push((bucket, row.try_get("minutes")?));
+        }
+    }
+
+    if history.is_empty() && !player_exists(ckey, &mut connection).await {
         connection.close().await?;
         return Err(Error::PlayerNotFound);
     }
 
     connection.close().await?;
 
-    Ok(roletimes)
+    Ok(history)
 }
 
 pub async fn get_jobs(job: &str, pool: &MySqlPool) -> Result<Vec<String>, Error> {
@@ -154,7 +395,7 @@ pub async fn get_jobs(job: &str, pool: &MySqlPool) -> Result<Vec<String>, Error>
 }
 
 pub async fn get_ckeys(
-    ckey: &str,
+    ckey: &Ckey,
     pool: &MySqlPool,
     config: &Config,
 ) -> Result<Vec<String>, Error> {
@@ -183,14 +424,14 @@ pub async fn get_ckeys(
 }
 
 pub async fn get_ban(
-    ckey: &str,
+    ckey: &Ckey,
     permanent: bool,
     since: Option<&str>,
     pool: &MySqlPool,
 ) -> Result<Vec<Ban>, Error> {
     let mut connection = pool.acquire().await?;
 
-    let mut sql = "SELECT id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban WHERE LOWER(ckey) = ?".to_string();
+    let mut sql = "SELECT id, bantime, round_id, GROUP_CONCAT(role ORDER BY role SEPARATOR ', ') AS roles, expiration_time, reason, ckey, a_ckey, edits, unbanned_datetime, unbanned_ckey FROM ban WHERE ckey = ?".to_string();
 
     if permanent {
         sql.push_str(" AND expiration_time IS NULL");
@@ -202,7 +443,7 @@ pub async fn get_ban(
 
     sql.push_str(" GROUP BY bantime");
 
-    let mut query = sqlx::query(&sql).bind(ckey.to_lowercase());
+    let mut query = sqlx::query(&sql).bind(ckey);
 
     if let Some(since) = since {
         query = query.bind(since);
@@ -227,8 +468,8 @@ pub async fn get_ban(
     Ok(bans)
 }
 
-pub async fn player_exists(ckey: &str, connection: &mut PoolConnection<MySql>) -> bool {
-    let query = sqlx::query("SELECT 1 FROM player WHERE LOWER(ckey) = ?").bind(ckey.to_lowercase());
+pub async fn player_exists(ckey: &Ckey, connection: &mut PoolConnection<MySql>) -> bool {
+    let query = sqlx::query("SELECT 1 FROM player WHERE ckey = ?").bind(ckey);
     connection.fetch_one(query).await.is_ok()
 }
 
@@ -266,9 +507,25 @@ pub async fn get_ic_names(ic_name: &str, pool: &MySqlPool) -> Result<Vec<IcName>
     Ok(ckeys)
 }
 
-pub async fn get_characters(ckey: &str, pool: &MySqlPool) -> Result<Vec<(String, i64)>, Error> {
+pub async fn get_characters(ckey: &Ckey, pool: &MySqlPool) -> Result<Vec<(String, i64)>, Error> {
     let mut connection = pool.acquire().await?;
 
+    let characters = characters_by_ckey(ckey, &mut connection).await?;
+
+    if characters.is_empty() && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    Ok(characters)
+}
+
+pub async fn characters_by_ckey(
+    ckey: &Ckey,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<(String, i64)>, Error> {
     const EXCLUDED_ROLES: &str = "('Operative', 'Wizard')";
 
     let query = sqlx::query(concatcp!(
@@ -276,7 +533,7 @@ pub async fn get_characters(ckey: &str, pool: &MySqlPool) -> Result<Vec<(String,
         EXCLUDED_ROLES,
         " GROUP BY character_name ORDER BY times DESC"
     ))
-    .bind(ckey.to_lowercase());
+    .bind(ckey);
 
     let mut characters = Vec::new();
 
@@ -290,23 +547,38 @@ pub async fn get_characters(ckey: &str, pool: &MySqlPool) -> Result<Vec<(String,
         }
     }
 
-    if characters.is_empty() && !player_exists(ckey, &mut connection).await {
+    Ok(characters)
+}
+
+pub async fn get_activity(
+    ckey: &Ckey,
+    days: u32,
+    pool: &MySqlPool,
+) -> Result<Vec<(String, i64)>, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let activity = activity_by_ckey(ckey, days, &mut connection).await?;
+
+    if activity.is_empty() && !player_exists(ckey, &mut connection).await {
         connection.close().await?;
         return Err(Error::PlayerNotFound);
     }
 
     connection.close().await?;
 
-    Ok(characters)
+    Ok(activity)
 }
 
-pub async fn get_activity(ckey: &str, pool: &MySqlPool) -> Result<Vec<(String, i64)>, Error> {
-    let mut connection = pool.acquire().await?;
-
+pub async fn activity_by_ckey(
+    ckey: &Ckey,
+    days: u32,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<(String, i64)>, Error> {
     let query = sqlx::query(
-        "SELECT DATE(datetime) AS date, COUNT(DISTINCT round_id) AS rounds FROM connection_log WHERE ckey = ? AND datetime >= DATE_SUB(CURDATE(), INTERVAL 180 DAY) GROUP BY date;"
+        "SELECT DATE(datetime) AS date, COUNT(DISTINCT round_id) AS rounds FROM connection_log WHERE ckey = ? AND datetime >= DATE_SUB(CURDATE(), INTERVAL ? DAY) GROUP BY date;"
     )
-    .bind(ckey.to_lowercase());
+    .bind(ckey)
+    .bind(days);
 
     let mut activity = Vec::new();
 
@@ -323,17 +595,67 @@ pub async fn get_activity(ckey: &str, pool: &MySqlPool) -> Result<Vec<(String, i
         }
     }
 
-    if activity.is_empty() && !player_exists(ckey, &mut connection).await {
+    Ok(activity)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActivityHeatmap {
+    pub days: u32,
+    pub timezone: String,
+    /// Connections per hour, indexed by weekday (Monday first) and then hour of day.
+    pub hours: [[i64; 24]; 7],
+}
+
+/// Aggregates a player's connections into an hour-of-week matrix. `timezone` is a UTC offset such
+/// as `+02:00` that connection times are shifted into before bucketing.
+pub async fn get_activity_heatmap(
+    ckey: &Ckey,
+    days: u32,
+    timezone: &str,
+    pool: &MySqlPool,
+) -> Result<ActivityHeatmap, Error> {
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT CAST(WEEKDAY(local) AS SIGNED) AS weekday, CAST(HOUR(local) AS SIGNED) AS hour, COUNT(*) AS connections FROM (SELECT CONVERT_TZ(datetime, '+00:00', ?) AS local FROM connection_log WHERE ckey = ? AND datetime >= DATE_SUB(CURDATE(), INTERVAL ? DAY)) AS connections WHERE local IS NOT NULL GROUP BY weekday, hour"
+    )
+    .bind(timezone)
+    .bind(ckey)
+    .bind(days);
+
+    let mut heatmap = ActivityHeatmap {
+        days,
+        timezone: timezone.to_string(),
+        hours: [[0; 24]; 7],
+    };
+
+    let mut empty = true;
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let weekday: i64 = row.try_get("weekday")?;
+            let hour: i64 = row.try_get("hour")?;
+
+            heatmap.hours[weekday as usize][hour as usize] = row.try_get("connections")?;
+            empty = false;
+        }
+    }
+
+    if empty && !player_exists(ckey, &mut connection).await {
         connection.close().await?;
         return Err(Error::PlayerNotFound);
     }
 
     connection.close().await?;
 
-    Ok(activity)
+    Ok(heatmap)
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize)]
 pub struct Achievement {
     pub achievement_key: String,
     pub achievement_version: u16,
@@ -343,16 +665,44 @@ pub struct Achievement {
     pub value: Option<i32>,
     #[serde(with = "crate::serde::datetime")]
     pub timestamp: NaiveDateTime,
+    /// Percentage of active players holding the achievement.
+    pub rarity: Option<f64>,
 }
 
 pub async fn get_achievements(
-    ckey: &str,
+    ckey: &Ckey,
     achievement_type: Option<&str>,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<Vec<Achievement>, Error> {
     let mut connection = pool.acquire().await?;
 
-    let mut sql = "SELECT a.value, a.last_updated, m.achievement_key, m.achievement_version, m.achievement_type, m.achievement_name, m.achievement_description FROM achievements a JOIN achievement_metadata m ON a.achievement_key = m.achievement_key WHERE LOWER(a.ckey) = ?".to_string();
+    let achievements = achievements_by_ckey(ckey, achievement_type, cache, &mut connection).await?;
+
+    if achievements.is_empty() && !player_exists(ckey, &mut connection).await {
+        connection.close().await?;
+        return Err(Error::PlayerNotFound);
+    }
+
+    connection.close().await?;
+
+    Ok(achievements)
+}
+
+pub async fn achievements_by_ckey(
+    ckey: &Ckey,
+    achievement_type: Option<&str>,
+    cache: &Cache,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Vec<Achievement>, Error> {
+    let rarities: HashMap<String, Option<f64>> =
+        achievement_rarities(ACTIVE_PLAYER_DAYS, cache, connection)
+            .await?
+            .into_iter()
+            .map(|achievement| (achievement.achievement_key, achievement.rarity))
+            .collect();
+
+    let mut sql = "SELECT a.value, a.last_updated, m.achievement_key, m.achievement_version, m.achievement_type, m.achievement_name, m.achievement_description FROM achievements a JOIN achievement_metadata m ON a.achievement_key = m.achievement_key WHERE a.ckey = ?".to_string();
 
     if achievement_type.is_some() {
         sql.push_str(" AND m.achievement_type = ?");
@@ -360,7 +710,7 @@ pub async fn get_achievements(
 
     sql.push_str(" ORDER BY a.last_updated DESC");
 
-    let mut query = sqlx::query(&sql).bind(ckey.to_lowercase());
+    let mut query = sqlx::query(&sql).bind(ckey);
 
     if let Some(achievement_type) = achievement_type {
         query = query.bind(achievement_type);
@@ -374,36 +724,45 @@ pub async fn get_achievements(
         while let Some(row) = rows.next().await {
             let achievement = row?;
 
+            let achievement_key: String = achievement.try_get("achievement_key")?;
+            let rarity = rarities.get(&achievement_key).copied().flatten();
+
             let achievement = Achievement {
-                achievement_key: achievement.try_get("achievement_key")?,
+                achievement_key,
                 achievement_version: achievement.try_get("achievement_version")?,
                 achievement_type: achievement.try_get("achievement_type")?,
                 achievement_name: achievement.try_get("achievement_name")?,
                 achievement_description: achievement.try_get("achievement_description")?,
                 value: achievement.try_get("value")?,
                 timestamp: achievement.try_get("last_updated")?,
+                rarity,
             };
 
             achievements.push(achievement);
         }
     }
 
-    if achievements.is_empty() && !player_exists(ckey, &mut connection).await {
-        connection.close().await?;
-        return Err(Error::PlayerNotFound);
-    }
-
-    connection.close().await?;
-
     Ok(achievements)
 }
 
 pub async fn get_favorite_character(
-    ckey: &str,
+    ckey: &Ckey,
     pool: &MySqlPool,
 ) -> Result<(String, String), Error> {
     let mut connection = pool.acquire().await?;
 
+    let character = favorite_character_by_ckey(ckey, &mut connection).await?;
+
+    connection.close().await?;
+
+    character.ok_or(Error::PlayerNotFound)
+}
+
+/// Returns the character name and job the player has played the most, if any.
+pub async fn favorite_character_by_ckey(
+    ckey: &Ckey,
+    connection: &mut PoolConnection<MySql>,
+) -> Result<Option<(String, String)>, Error> {
     const EXCLUDED_ROLES: &str = "('Operative', 'Wizard')";
 
     let query = sqlx::query(concatcp!(
@@ -411,18 +770,13 @@ pub async fn get_favorite_character(
         EXCLUDED_ROLES,
         " GROUP BY character_name, job ORDER BY times DESC LIMIT 1"
     ))
-    .bind(ckey.to_lowercase());
+    .bind(ckey);
 
-    let Ok(row) = connection.fetch_one(query).await else {
-        return Err(Error::PlayerNotFound);
+    let Some(row) = connection.fetch_optional(query).await? else {
+        return Ok(None);
     };
 
-    let character_name = row.try_get("character_name")?;
-    let job = row.try_get("job")?;
-
-    connection.close().await?;
-
-    Ok((character_name, job))
+    Ok(Some((row.try_get("character_name")?, row.try_get("job")?)))
 }
 
 #[derive(Serialize, Debug, FromRow)]
@@ -443,7 +797,7 @@ pub struct TicketGroup {
 }
 
 pub async fn get_tickets(
-    ckey: &str,
+    ckey: &Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     pool: &MySqlPool,
@@ -456,23 +810,23 @@ pub async fn get_tickets(
 
     let total_count: i64 = sqlx::query_scalar(
         "SELECT COUNT(DISTINCT ticket, round_id) FROM ticket 
-         WHERE action = 'Ticket Opened' AND ((LOWER(sender) = ? AND recipient IS NULL) OR (LOWER(recipient) = ?))"
+         WHERE action = 'Ticket Opened' AND ((sender = ? AND recipient IS NULL) OR (recipient = ?))"
     )
-    .bind(ckey.to_lowercase())
-    .bind(ckey.to_lowercase())
+    .bind(ckey)
+    .bind(ckey)
     .fetch_one(&mut *connection)
     .await?;
 
     let target_tickets = sqlx::query(
         "SELECT ticket, round_id, MAX(timestamp) as latest_time 
          FROM ticket 
-         WHERE action = 'Ticket Opened' AND ((LOWER(sender) = ? AND recipient IS NULL) OR (LOWER(recipient) = ?)) 
+         WHERE action = 'Ticket Opened' AND ((sender = ? AND recipient IS NULL) OR (recipient = ?)) 
          GROUP BY round_id, ticket 
          ORDER BY latest_time DESC, round_id DESC, ticket DESC 
-         LIMIT ? OFFSET ?"
+         LIMIT ? OFFSET ?",
     )
-    .bind(ckey.to_lowercase())
-    .bind(ckey.to_lowercase())
+    .bind(ckey)
+    .bind(ckey)
     .bind(fetch_size)
     .bind(offset)
     .fetch_all(&mut *connection)
@@ -532,7 +886,7 @@ pub struct Message {
 }
 
 pub async fn get_messages(
-    ckey: &str,
+    ckey: &Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     pool: &MySqlPool,
@@ -543,9 +897,9 @@ pub async fn get_messages(
 
     let mut connection = pool.acquire().await?;
 
-    let sql = "SELECT COUNT(*) FROM messages WHERE type IN ('message', 'message sent') AND LOWER(targetckey) = ? AND secret = 0 AND deleted = 0 AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)".to_string();
+    let sql = "SELECT COUNT(*) FROM messages WHERE type IN ('message', 'message sent') AND targetckey = ? AND secret = 0 AND deleted = 0 AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)".to_string();
 
-    let query = sqlx::query_scalar(&sql).bind(ckey.to_lowercase());
+    let query = sqlx::query_scalar(&sql).bind(ckey);
 
     let total_count = query.fetch_one(&mut *connection).await?;
 
@@ -557,14 +911,14 @@ pub async fn get_messages(
             DATEDIFF(NOW(), timestamp) AS days_passed
         FROM messages
         WHERE type IN ('message', 'message sent') 
-          AND LOWER(targetckey) = ? 
+          AND targetckey = ? 
           AND secret = 0 
           AND deleted = 0 
           AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)
         ORDER BY timestamp DESC 
         LIMIT ? OFFSET ?",
     )
-    .bind(ckey.to_lowercase())
+    .bind(ckey)
     .bind(fetch_size)
     .bind(offset)
     .fetch_all(&mut *connection)
@@ -581,7 +935,7 @@ pub async fn get_messages(
 }
 
 pub async fn get_notes(
-    ckey: &str,
+    ckey: &Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     pool: &MySqlPool,
@@ -592,9 +946,9 @@ pub async fn get_notes(
 
     let mut connection = pool.acquire().await?;
 
-    let sql = "SELECT COUNT(*) FROM messages WHERE type = 'note' AND LOWER(targetckey) = ? AND secret = 0 AND deleted = 0 AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)".to_string();
+    let sql = "SELECT COUNT(*) FROM messages WHERE type = 'note' AND targetckey = ? AND secret = 0 AND deleted = 0 AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)".to_string();
 
-    let query = sqlx::query_scalar(&sql).bind(ckey.to_lowercase());
+    let query = sqlx::query_scalar(&sql).bind(ckey);
 
     let total_count = query.fetch_one(&mut *connection).await?;
 
@@ -606,14 +960,14 @@ pub async fn get_notes(
             DATEDIFF(NOW(), timestamp) AS days_passed
         FROM messages
         WHERE type = 'note' 
-          AND LOWER(targetckey) = ? 
+          AND targetckey = ? 
           AND secret = 0 
           AND deleted = 0 
           AND (expire_timestamp > NOW() OR expire_timestamp IS NULL)
         ORDER BY timestamp DESC 
         LIMIT ? OFFSET ?",
     )
-    .bind(ckey.to_lowercase())
+    .bind(ckey)
     .bind(fetch_size)
     .bind(offset)
     .fetch_all(&mut *connection)
@@ -643,7 +997,7 @@ pub struct ManifestData {
 }
 
 pub async fn get_player_rounds(
-    ckey: &str,
+    ckey: &Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     pool: &MySqlPool,
@@ -654,16 +1008,16 @@ pub async fn get_player_rounds(
 
     let mut connection = pool.acquire().await?;
 
-    let sql = "SELECT COUNT(*) FROM manifest WHERE LOWER(ckey) = ?".to_string();
+    let sql = "SELECT COUNT(*) FROM manifest WHERE ckey = ?".to_string();
 
-    let query = sqlx::query_scalar(&sql).bind(ckey.to_lowercase());
+    let query = sqlx::query_scalar(&sql).bind(ckey);
 
     let total_count = query.fetch_one(&mut *connection).await?;
 
     let rounds = sqlx::query_as::<_, ManifestData>(
-        "SELECT id, round_id, ckey, character_name, job, special, latejoin, timestamp FROM manifest WHERE LOWER(ckey) = ? ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+        "SELECT id, round_id, ckey, character_name, job, special, latejoin, timestamp FROM manifest WHERE ckey = ? ORDER BY timestamp DESC LIMIT ? OFFSET ?"
     )
-    .bind(ckey.to_lowercase())
+    .bind(ckey)
     .bind(fetch_size)
     .bind(offset)
     .fetch_all(&mut *connection)
@@ -692,20 +1046,20 @@ pub struct Friendship {
 }
 
 pub async fn get_friends(
-    ckey: &str,
+    ckey: &Ckey,
     pool: &MySqlPool,
     config: &Config,
 ) -> Result<Vec<Friendship>, Error> {
     let mut connection = pool.acquire().await?;
 
     let sql = format!(
-        "SELECT * FROM {}.friendship WHERE (LOWER(user_ckey) = ? OR LOWER(friend_ckey) = ?) AND status = 'accepted'",
+        "SELECT * FROM {}.friendship WHERE (user_ckey = ? OR friend_ckey = ?) AND status = 'accepted'",
         config.database.api_database
     );
 
     let friends = sqlx::query_as::<_, Friendship>(&sql)
-        .bind(ckey.to_lowercase())
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
+        .bind(ckey)
         .fetch_all(&mut *connection)
         .await?;
 
@@ -720,29 +1074,29 @@ pub async fn get_friends(
 }
 
 pub async fn get_friendship_invites(
-    ckey: &str,
+    ckey: &Ckey,
     pool: &MySqlPool,
     config: &Config,
 ) -> Result<(Vec<Friendship>, Vec<Friendship>), Error> {
     let mut connection = pool.acquire().await?;
 
     let sql = format!(
-        "SELECT * FROM {}.friendship WHERE LOWER(friend_ckey) = ? AND status = 'pending'",
+        "SELECT * FROM {}.friendship WHERE friend_ckey = ? AND status = 'pending'",
         config.database.api_database
     );
 
     let received_requests = sqlx::query_as::<_, Friendship>(&sql)
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
         .fetch_all(&mut *connection)
         .await?;
 
     let sql = format!(
-        "SELECT * FROM {}.friendship WHERE LOWER(user_ckey) = ? AND status = 'pending'",
+        "SELECT * FROM {}.friendship WHERE user_ckey = ? AND status = 'pending'",
         config.database.api_database
     );
 
     let sent_requests = sqlx::query_as::<_, Friendship>(&sql)
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
         .fetch_all(&mut *connection)
         .await?;
 
@@ -760,23 +1114,23 @@ pub async fn get_friendship_invites(
 }
 
 pub async fn check_friendship(
-    ckey: &str,
-    friend: &str,
+    ckey: &Ckey,
+    friend: &Ckey,
     pool: &MySqlPool,
     config: &Config,
 ) -> Result<Option<Friendship>, Error> {
     let mut connection = pool.acquire().await?;
 
     let sql = format!(
-        "SELECT * FROM {}.friendship WHERE ((LOWER(user_ckey) = ? AND LOWER(friend_ckey) = ?) OR (LOWER(user_ckey) = ? AND LOWER(friend_ckey) = ?)) LIMIT 1",
+        "SELECT * FROM {}.friendship WHERE ((user_ckey = ? AND friend_ckey = ?) OR (user_ckey = ? AND friend_ckey = ?)) LIMIT 1",
         config.database.api_database
     );
 
     let friendship = sqlx::query_as::<_, Friendship>(&sql)
-        .bind(ckey.to_lowercase())
-        .bind(friend.to_lowercase())
-        .bind(friend.to_lowercase())
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
+        .bind(friend)
+        .bind(friend)
+        .bind(ckey)
         .fetch_optional(&mut *connection) // connection.acquire()'a gerek yok, direkt pool kullanabilirsin
         .await?;
 
@@ -794,8 +1148,8 @@ pub async fn check_friendship(
 }
 
 pub async fn add_friend(
-    ckey: &str,
-    friend: &str,
+    ckey: &Ckey,
+    friend: &Ckey,
     pool: &MySqlPool,
     config: &Config,
 ) -> Result<Option<Friendship>, Error> {
@@ -815,19 +1169,19 @@ pub async fn add_friend(
         config.database.api_database
     );
     let result = sqlx::query(&sql)
-        .bind(ckey.to_lowercase())
-        .bind(friend.to_lowercase())
+        .bind(ckey)
+        .bind(friend)
         .execute(&mut *connection)
         .await?;
 
     if result.rows_affected() > 0 {
         let sql = format!(
-            "SELECT * FROM {}.friendship WHERE LOWER(user_ckey) = ? AND LOWER(friend_ckey) = ?",
+            "SELECT * FROM {}.friendship WHERE user_ckey = ? AND friend_ckey = ?",
             config.database.api_database
         );
         let updated_row = sqlx::query_as::<_, Friendship>(&sql)
-            .bind(ckey.to_lowercase())
-            .bind(friend.to_lowercase())
+            .bind(ckey)
+            .bind(friend)
             .fetch_one(&mut *connection)
             .await?;
 
@@ -840,7 +1194,7 @@ pub async fn add_friend(
 }
 
 pub async fn remove_friend(
-    ckey: &str,
+    ckey: &Ckey,
     friendship_id: i32,
     pool: &MySqlPool,
     config: &Config,
@@ -853,13 +1207,13 @@ pub async fn remove_friend(
     }
 
     let sql = format!(
-        "UPDATE {}.friendship SET status = 'removed' WHERE id = ? AND (LOWER(user_ckey) = ? OR LOWER(friend_ckey) = ?) AND status = 'accepted'",
+        "UPDATE {}.friendship SET status = 'removed' WHERE id = ? AND (user_ckey = ? OR friend_ckey = ?) AND status = 'accepted'",
         config.database.api_database
     );
     let result = sqlx::query(&sql)
         .bind(friendship_id)
-        .bind(ckey.to_lowercase())
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
+        .bind(ckey)
         .execute(&mut *connection)
         .await?;
 
@@ -882,7 +1236,7 @@ pub async fn remove_friend(
 }
 
 pub async fn accept_friend(
-    ckey: &str,
+    ckey: &Ckey,
     friendship_id: i32,
     pool: &MySqlPool,
     config: &Config,
@@ -895,12 +1249,12 @@ pub async fn accept_friend(
     }
 
     let sql = format!(
-        "UPDATE {}.friendship SET status = 'accepted' WHERE id = ? AND LOWER(friend_ckey) = ? AND status = 'pending'",
+        "UPDATE {}.friendship SET status = 'accepted' WHERE id = ? AND friend_ckey = ? AND status = 'pending'",
         config.database.api_database
     );
     let result = sqlx::query(&sql)
         .bind(friendship_id)
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
         .execute(&mut *connection)
         .await?;
 
@@ -922,7 +1276,7 @@ pub async fn accept_friend(
 }
 
 pub async fn decline_friend(
-    ckey: &str,
+    ckey: &Ckey,
     friendship_id: i32,
     pool: &MySqlPool,
     config: &Config,
@@ -935,14 +1289,14 @@ pub async fn decline_friend(
     }
 
     let sql = format!(
-        "UPDATE {}.friendship SET status = 'declined' WHERE id = ? AND (LOWER(user_ckey) = ? OR LOWER(friend_ckey) = ?) AND status = 'pending'",
+        "UPDATE {}.friendship SET status = 'declined' WHERE id = ? AND (user_ckey = ? OR friend_ckey = ?) AND status = 'pending'",
         config.database.api_database
     );
 
     let result = sqlx::query(&sql)
         .bind(friendship_id)
-        .bind(ckey.to_lowercase())
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
+        .bind(ckey)
         .execute(&mut *connection)
         .await?;
 
@@ -964,7 +1318,7 @@ pub async fn decline_friend(
 }
 
 pub async fn hide_ckey(
-    ckey: &str,
+    ckey: &Ckey,
     hid_by: i64,
     pool: &MySqlPool,
     config: &Config,
@@ -975,7 +1329,7 @@ pub async fn hide_ckey(
         "SELECT 1 FROM {}.hid_ckeys_autocomplete WHERE ckey = ? AND valid = 1",
         config.database.api_database
     );
-    let query = sqlx::query(&sql).bind(ckey.to_lowercase());
+    let query = sqlx::query(&sql).bind(ckey);
 
     if connection.fetch_optional(query).await?.is_some() {
         return Ok(false);
@@ -985,7 +1339,7 @@ pub async fn hide_ckey(
         "INSERT INTO {}.hid_ckeys_autocomplete (ckey, hid_by, valid) VALUES (?, ?, 1)",
         config.database.api_database
     );
-    let query = sqlx::query(&sql).bind(ckey.to_lowercase()).bind(hid_by);
+    let query = sqlx::query(&sql).bind(ckey).bind(hid_by);
 
     connection.execute(query).await?;
     connection.close().await?;
@@ -994,7 +1348,7 @@ pub async fn hide_ckey(
 }
 
 pub async fn unhide_ckey(
-    ckey: &str,
+    ckey: &Ckey,
     unhid_by: i64,
     pool: &MySqlPool,
     config: &Config,
@@ -1005,7 +1359,7 @@ pub async fn unhide_ckey(
         "SELECT 1 FROM {}.hid_ckeys_autocomplete WHERE ckey = ? AND valid = 1",
         config.database.api_database
     );
-    let query = sqlx::query(&sql).bind(ckey.to_lowercase());
+    let query = sqlx::query(&sql).bind(ckey);
 
     if connection.fetch_optional(query).await?.is_none() {
         return Ok(false);
@@ -1015,7 +1369,7 @@ pub async fn unhide_ckey(
         "UPDATE {}.hid_ckeys_autocomplete SET valid = 0, unhid_by = ? WHERE ckey = ? AND valid = 1",
         config.database.api_database
     );
-    let query = sqlx::query(&sql).bind(unhid_by).bind(ckey.to_lowercase());
+    let query = sqlx::query(&sql).bind(unhid_by).bind(ckey);
 
     connection.execute(query).await?;
     connection.close().await?;
@@ -1024,7 +1378,7 @@ pub async fn unhide_ckey(
 }
 
 pub async fn lookup_player(
-    ckey: Option<&str>,
+    ckey: Option<&Ckey>,
     ip: Option<&str>,
     cid: Option<i64>,
     pool: &MySqlPool,
@@ -1065,7 +1419,7 @@ pub async fn lookup_player(
     let mut query = sqlx::query(&sql);
 
     if let Some(ckey) = ckey {
-        query = query.bind(ckey.to_lowercase()).bind(ckey.to_lowercase());
+        query = query.bind(ckey).bind(ckey);
     } else if let Some(ip) = ip {
         query = query.bind(ip).bind(ip);
     } else if let Some(cid) = cid {
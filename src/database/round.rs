@@ -1,11 +1,18 @@
-use chrono::NaiveDateTime;
-use rocket::futures::StreamExt as _;
+use std::net::SocketAddr;
+
+use chrono::{Duration, NaiveDateTime};
+use rocket::{futures::StreamExt as _, FromForm, FromFormField};
 use serde::Serialize;
 
 use serde_json::Value;
-use sqlx::{Executor as _, MySqlPool, Row as _};
+use sqlx::{mysql::MySqlArguments, query::Query, Executor as _, MySql, MySqlPool, Row as _};
 
-use crate::{config::Config, database::*};
+use crate::{
+    cache::Cache,
+    config::{Config, Server},
+    database::*,
+    date::OptionalDate,
+};
 
 use super::error::Error;
 
@@ -17,6 +24,9 @@ pub struct RoundData {
     pub map_name: Option<String>,
     pub station_name: Option<String>,
     pub commit_hash: Option<String>,
+    pub game_mode: Option<String>,
+    pub game_mode_result: Option<String>,
+    pub end_state: Option<String>,
     pub population: Vec<(String, i64)>,
     pub shuttle_name: Option<String>,
     pub nukedisk: Option<Value>,
@@ -36,9 +46,10 @@ pub struct RoundData {
 pub async fn get_round(
     round_id: i32,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<RoundData, Error> {
-    let current_round_id = get_round_id(config).await?;
+    let current_round_id = get_round_id(config, cache).await?;
 
     if let Some(current_round_id) = current_round_id {
         if current_round_id == round_id {
@@ -87,7 +98,7 @@ pub async fn get_round(
     };
 
     let query = sqlx::query(
-        "SELECT id, server_ip, server_port, map_name, station_name, commit_hash, shuttle_name, initialize_datetime, start_datetime, shutdown_datetime, end_datetime FROM round WHERE id = ? ORDER BY id DESC",
+        "SELECT id, server_ip, server_port, map_name, station_name, commit_hash, game_mode, game_mode_result, end_state, shuttle_name, initialize_datetime, start_datetime, shutdown_datetime, end_datetime FROM round WHERE id = ? ORDER BY id DESC",
     )
     .bind(round_id);
 
@@ -105,6 +116,9 @@ pub async fn get_round(
         map_name: row.try_get("map_name")?,
         station_name: row.try_get("station_name")?,
         commit_hash: row.try_get("commit_hash")?,
+        game_mode: row.try_get("game_mode")?,
+        game_mode_result: row.try_get("game_mode_result")?,
+        end_state: row.try_get("end_state")?,
         shuttle_name: row.try_get("shuttle_name")?,
         initialize_datetime: row.try_get("initialize_datetime")?,
         start_datetime: row.try_get("start_datetime")?,
@@ -122,6 +136,26 @@ pub async fn get_round(
     Ok(round)
 }
 
+/// Formats `datetime` as `HH:MM:SS` since `initialize_date`, or as a time of day without one.
+/// Anything logged before initialization gets a leading `-`.
+pub(super) fn format_offset(
+    datetime: NaiveDateTime,
+    initialize_date: Option<NaiveDateTime>,
+) -> String {
+    if let Some(start) = initialize_date {
+        let duration = datetime - start;
+        let sign = if duration < Duration::zero() { "-" } else { "" };
+        let duration = duration.abs();
+
+        let hours = duration.num_hours();
+        let minutes = duration.num_minutes() % 60;
+        let seconds = duration.num_seconds() % 60;
+        format!("{sign}{hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        datetime.format("%H:%M:%S").to_string()
+    }
+}
+
 pub async fn get_population(
     round_id: i32,
     initialize_date: Option<NaiveDateTime>,
@@ -144,18 +178,10 @@ pub async fn get_population(
 
             let datetime: NaiveDateTime = row.try_get("time")?;
 
-            let time_str = if let Some(start) = initialize_date {
-                let duration = datetime - start;
-
-                let hours = duration.num_hours();
-                let minutes = duration.num_minutes() % 60;
-                let seconds = duration.num_seconds() % 60;
-                format!("{hours:02}:{minutes:02}:{seconds:02}")
-            } else {
-                datetime.format("%H:%M:%S").to_string()
-            };
-
-            population.push((time_str, row.try_get("playercount")?));
+            population.push((
+                format_offset(datetime, initialize_date),
+                row.try_get("playercount")?,
+            ));
         }
     }
 
@@ -164,14 +190,191 @@ pub async fn get_population(
     Ok(population)
 }
 
+/// Highest player count recorded during the round.
+const POPULATION: &str =
+    "(SELECT MAX(playercount) FROM legacy_population p WHERE p.round_id = round.id)";
+
+const DURATION: &str = "TIMESTAMPDIFF(MINUTE, start_datetime, end_datetime)";
+
+#[derive(Debug, Clone, Copy, Default, FromFormField)]
+pub enum RoundSort {
+    #[default]
+    Newest,
+    Oldest,
+    Longest,
+    Shortest,
+    Population,
+}
+
+impl RoundSort {
+    fn order_by(self) -> String {
+        match self {
+            RoundSort::Newest => "id DESC".to_string(),
+            RoundSort::Oldest => "id ASC".to_string(),
+            RoundSort::Longest => format!("{DURATION} IS NULL, {DURATION} DESC, id DESC"),
+            RoundSort::Shortest => format!("{DURATION} IS NULL, {DURATION} ASC, id DESC"),
+            RoundSort::Population => format!("{POPULATION} IS NULL, {POPULATION} DESC, id DESC"),
+        }
+    }
+}
+
+/// The public address a configured server logs its rows under. `address` is only the topic
+/// address the API talks to, which is usually loopback.
+pub(super) fn game_address(server: &Server) -> Option<SocketAddr> {
+    server.connection_address.parse().ok()
+}
+
+/// Resolves the name of a configured server to the address its rows are logged under.
+pub(super) fn server_address(
+    name: Option<&str>,
+    config: &Config,
+) -> Result<Option<SocketAddr>, Error> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    config
+        .servers
+        .iter()
+        .find(|server| server.name.eq_ignore_ascii_case(name))
+        .and_then(game_address)
+        .map(Some)
+        .ok_or(Error::ServerNotFound)
+}
+
+#[derive(Debug, FromForm)]
+pub struct RoundFilters<'r> {
+    /// Prefix of the round id, used for autocompletion.
+    pub round_id: Option<i32>,
+    pub map: Option<&'r str>,
+    /// Name of a server from the config.
+    pub server: Option<&'r str>,
+    pub game_mode: Option<&'r str>,
+    pub storyteller: Option<&'r str>,
+    pub dynamic_tier: Option<i32>,
+    pub end_state: Option<&'r str>,
+    pub from: OptionalDate,
+    pub to: OptionalDate,
+    /// Minimum duration in minutes.
+    pub min_duration: Option<u32>,
+    pub min_population: Option<u32>,
+    pub sort: Option<RoundSort>,
+}
+
+impl RoundFilters<'_> {
+    fn where_sql(&self, server: Option<SocketAddr>) -> String {
+        let mut sql = String::new();
+
+        if self.round_id.is_some() {
+            sql.push_str(" AND id LIKE CONCAT(?, '%')");
+        }
+
+        if self.map.is_some() {
+            sql.push_str(" AND map_name = ?");
+        }
+
+        if server.is_some() {
+            sql.push_str(" AND server_ip = INET_ATON(?) AND server_port = ?");
+        }
+
+        if self.game_mode.is_some() {
+            sql.push_str(" AND game_mode = ?");
+        }
+
+        if self.storyteller.is_some() {
+            sql.push_str(" AND EXISTS (SELECT 1 FROM feedback f WHERE f.round_id = round.id AND f.key_name = 'storyteller' AND JSON_UNQUOTE(JSON_EXTRACT(f.json, '$.data.\"1\".name')) = ?)");
+        }
+
+        if self.dynamic_tier.is_some() {
+            sql.push_str(" AND EXISTS (SELECT 1 FROM feedback f WHERE f.round_id = round.id AND f.key_name = 'dynamic_tier' AND JSON_UNQUOTE(JSON_EXTRACT(f.json, '$.data.\"1\".tier')) = ?)");
+        }
+
+        if self.end_state.is_some() {
+            sql.push_str(" AND end_state = ?");
+        }
+
+        if self.from.is_some() {
+            sql.push_str(" AND initialize_datetime >= ?");
+        }
+
+        if self.to.is_some() {
+            sql.push_str(" AND initialize_datetime < ?");
+        }
+
+        if self.min_duration.is_some() {
+            sql.push_str(&format!(" AND {DURATION} >= ?"));
+        }
+
+        if self.min_population.is_some() {
+            sql.push_str(&format!(" AND {POPULATION} >= ?"));
+        }
+
+        sql
+    }
+
+    fn bind<'q>(
+        &'q self,
+        server: Option<SocketAddr>,
+        mut query: Query<'q, MySql, MySqlArguments>,
+    ) -> Query<'q, MySql, MySqlArguments> {
+        if let Some(round_id) = self.round_id {
+            query = query.bind(round_id);
+        }
+
+        if let Some(map) = self.map {
+            query = query.bind(map);
+        }
+
+        if let Some(server) = server {
+            query = query.bind(server.ip().to_string()).bind(server.port());
+        }
+
+        if let Some(game_mode) = self.game_mode {
+            query = query.bind(game_mode);
+        }
+
+        if let Some(storyteller) = self.storyteller {
+            query = query.bind(storyteller);
+        }
+
+        if let Some(dynamic_tier) = self.dynamic_tier {
+            query = query.bind(dynamic_tier.to_string());
+        }
+
+        if let Some(end_state) = self.end_state {
+            query = query.bind(end_state);
+        }
+
+        if let Some(from) = *self.from {
+            query = query.bind(from);
+        }
+
+        if let Some(to) = *self.to {
+            query = query.bind(to);
+        }
+
+        if let Some(min_duration) = self.min_duration {
+            query = query.bind(min_duration);
+        }
+
+        if let Some(min_population) = self.min_population {
+            query = query.bind(min_population);
+        }
+
+        query
+    }
+}
+
 pub async fn get_rounds(
     fetch_size: Option<i32>,
     page: Option<i32>,
-    autocomplete_round_id: Option<i32>,
+    filters: &RoundFilters<'_>,
     config: &Config,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<(Vec<RoundData>, i64), Error> {
-    let round_id = get_round_id(config).await?;
+    let round_id = get_round_id(config, cache).await?;
+    let server = server_address(filters.server, config)?;
 
     let fetch_size = fetch_size.unwrap_or(20);
     let page = page.unwrap_or(1);
@@ -185,41 +388,38 @@ pub async fn get_rounds(
         sql.push_str(" AND id < ?");
     }
 
-    if autocomplete_round_id.is_some() {
-        sql.push_str(" AND id LIKE CONCAT(?, '%')");
-    }
+    sql.push_str(&filters.where_sql(server));
 
-    let mut query = sqlx::query_scalar(&sql);
+    let mut query = sqlx::query(&sql);
 
     if let Some(round_id) = round_id {
         query = query.bind(round_id);
     }
-    if let Some(autocomplete_round_id) = autocomplete_round_id {
-        query = query.bind(autocomplete_round_id);
-    }
-    let total_count = query.fetch_one(&mut *connection).await?;
+
+    let total_count = connection
+        .fetch_one(filters.bind(server, query))
+        .await?
+        .try_get(0)?;
 
     let mut sql = "SELECT id, server_ip, server_port, map_name, station_name, commit_hash, game_mode, game_mode_result, end_state, shuttle_name, initialize_datetime, start_datetime, shutdown_datetime, end_datetime FROM round WHERE map_name IS NOT NULL".to_string();
 
     if round_id.is_some() {
         sql.push_str(" AND id < ?");
     }
-    if autocomplete_round_id.is_some() {
-        sql.push_str(" AND id LIKE CONCAT(?, '%')");
-    }
 
-    sql.push_str(" ORDER BY id DESC LIMIT ? OFFSET ?");
+    sql.push_str(&filters.where_sql(server));
+    sql.push_str(&format!(
+        " ORDER BY {} LIMIT ? OFFSET ?",
+        filters.sort.unwrap_or_default().order_by()
+    ));
 
     let mut query = sqlx::query(&sql);
 
     if let Some(round_id) = round_id {
         query = query.bind(round_id);
     }
-    if let Some(autocomplete_round_id) = autocomplete_round_id {
-        query = query.bind(autocomplete_round_id);
-    }
 
-    query = query.bind(fetch_size).bind(offset);
+    let query = filters.bind(server, query).bind(fetch_size).bind(offset);
 
     let mut rounds = Vec::new();
 
@@ -236,6 +436,9 @@ pub async fn get_rounds(
                 map_name: row.try_get("map_name")?,
                 station_name: row.try_get("station_name")?,
                 commit_hash: row.try_get("commit_hash")?,
+                game_mode: row.try_get("game_mode")?,
+                game_mode_result: row.try_get("game_mode_result")?,
+                end_state: row.try_get("end_state")?,
                 shuttle_name: row.try_get("shuttle_name")?,
                 initialize_datetime: row.try_get("initialize_datetime")?,
                 start_datetime: row.try_get("start_datetime")?,
@@ -256,3 +459,97 @@ pub async fn get_rounds(
 
     Ok((rounds, total_count))
 }
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    pub character_name: String,
+    pub job: String,
+    pub special: Option<String>,
+    pub latejoin: bool,
+    #[serde(with = "crate::serde::datetime")]
+    pub timestamp: NaiveDateTime,
+    /// `None` when the player has hidden their ckey and `show_hidden` wasn't set.
+    pub ckey: Option<String>,
+}
+
+/// Lists the crew of a finished round. Rounds without an end time or still running on any server
+/// are refused.
+pub async fn get_round_manifest(
+    round_id: i32,
+    show_hidden: bool,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<ManifestEntry>, Error> {
+    if get_round_ids(config, cache).await.contains(&round_id) {
+        return Err(Error::RoundNotFound);
+    }
+
+    let mut connection = pool.acquire().await?;
+
+    let query =
+        sqlx::query("SELECT 1 FROM round WHERE id = ? AND end_datetime IS NOT NULL").bind(round_id);
+
+    if connection.fetch_optional(query).await?.is_none() {
+        connection.close().await?;
+        return Err(Error::RoundNotFound);
+    }
+
+    let sql = format!(
+        "SELECT m.ckey, m.character_name, m.job, m.special, m.latejoin, m.timestamp, i.ckey AS hidden FROM {}.manifest m LEFT JOIN {}.hid_ckeys_autocomplete i ON i.ckey = m.ckey AND i.valid = 1 WHERE m.round_id = ? ORDER BY m.timestamp ASC, m.id ASC",
+        config.database.game_database, config.database.api_database
+    );
+
+    let query = sqlx::query(&sql).bind(round_id);
+
+    let mut manifest = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let hidden: Option<String> = row.try_get("hidden")?;
+
+            manifest.push(ManifestEntry {
+                character_name: row.try_get("character_name")?,
+                job: row.try_get("job")?,
+                special: row.try_get("special")?,
+                latejoin: row.try_get("latejoin")?,
+                timestamp: row.try_get("timestamp")?,
+                ckey: match hidden {
+                    Some(_) if !show_hidden => None,
+                    _ => Some(row.try_get("ckey")?),
+                },
+            });
+        }
+    }
+
+    connection.close().await?;
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn formats_offsets_from_initialization() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+
+        let at = |seconds| start + Duration::seconds(seconds);
+
+        assert_eq!(format_offset(at(0), Some(start)), "00:00:00");
+        assert_eq!(format_offset(at(3725), Some(start)), "01:02:05");
+        assert_eq!(format_offset(at(-3725), Some(start)), "-01:02:05");
+        assert_eq!(format_offset(at(-5), Some(start)), "-00:00:05");
+        assert_eq!(format_offset(at(90), None), "12:01:30");
+    }
+}
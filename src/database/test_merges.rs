@@ -2,12 +2,12 @@ use std::collections::HashSet;
 
 use chrono::NaiveDateTime;
 use rocket::futures::StreamExt as _;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{Executor as _, MySqlPool, Row as _};
 
 use super::error::Error;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestMerge {
     round_id: u32,
     #[serde(with = "crate::serde::datetime")]
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use rocket::{futures::StreamExt as _, FromForm};
+use serde::Serialize;
+use sqlx::{
+    mysql::MySqlArguments, query::Query, Executor as _, FromRow as _, MySql, MySqlPool, Row as _,
+};
+
+use crate::{ckey::Ckey, date::OptionalDate};
+
+use super::{ban::escape_like, error::Error, TicketLog};
+
+/// Ticket actions that count as a response to whoever opened the ticket.
+pub(super) const RESPONSE_ACTIONS: &str = "('Reply', 'Resolved', 'Closed', 'Rejected', 'IC Issue')";
+
+const CLOSING_ACTIONS: &str = "('Resolved', 'Closed', 'Rejected', 'IC Issue')";
+
+const SAME_TICKET: &str = "r.round_id = o.round_id AND r.ticket = o.ticket";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketState {
+    Open,
+    Resolved,
+    Closed,
+    Rejected,
+    IcIssue,
+}
+
+impl TicketState {
+    fn from_action(action: Option<&str>) -> Self {
+        match action {
+            Some("Resolved") => TicketState::Resolved,
+            Some("Closed") => TicketState::Closed,
+            Some("Rejected") => TicketState::Rejected,
+            Some("IC Issue") => TicketState::IcIssue,
+            _ => TicketState::Open,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Ticket {
+    pub ticket_id: u32,
+    pub round_id: Option<u32>,
+    pub opened_by: Option<String>,
+    pub recipient: Option<String>,
+    #[serde(with = "crate::serde::datetime")]
+    pub opened_at: NaiveDateTime,
+    /// Seconds between a player opening the ticket and staff first responding to it. Tickets opened
+    /// by an admin have none, since the player answering them isn't a response.
+    pub first_response: Option<i64>,
+    pub state: TicketState,
+    pub message_count: i64,
+    pub logs: Vec<TicketLog>,
+}
+
+#[derive(Debug, FromForm)]
+pub struct TicketFilters<'r> {
+    pub admin: Option<Ckey>,
+    pub round_id: Option<u32>,
+    pub action: Option<&'r str>,
+    pub from: OptionalDate,
+    pub to: OptionalDate,
+    pub search: Option<&'r str>,
+}
+
+impl TicketFilters<'_> {
+    fn where_sql(&self) -> String {
+        let mut sql = " WHERE o.action = 'Ticket Opened'".to_string();
+
+        if self.admin.is_some() {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM ticket r WHERE {SAME_TICKET} AND r.sender = ? AND (r.action <> 'Ticket Opened' OR r.recipient IS NOT NULL))"
+            ));
+        }
+
+        if self.round_id.is_some() {
+            sql.push_str(" AND o.round_id = ?");
+        }
+
+        if self.action.is_some() {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM ticket r WHERE {SAME_TICKET} AND r.action = ?)"
+            ));
+        }
+
+        if self.from.is_some() {
+            sql.push_str(" AND o.timestamp >= ?");
+        }
+
+        if self.to.is_some() {
+            sql.push_str(" AND o.timestamp < ?");
+        }
+
+        if self.search.is_some() {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM ticket r WHERE {SAME_TICKET} AND r.message LIKE ?)"
+            ));
+        }
+
+        sql
+    }
+
+    fn bind<'q>(
+        &'q self,
+        mut query: Query<'q, MySql, MySqlArguments>,
+    ) -> Query<'q, MySql, MySqlArguments> {
+        if let Some(admin) = &self.admin {
+            query = query.bind(admin);
+        }
+
+        if let Some(round_id) = self.round_id {
+            query = query.bind(round_id);
+        }
+
+        if let Some(action) = self.action {
+            query = query.bind(action);
+        }
+
+        if let Some(from) = *self.from {
+            query = query.bind(from);
+        }
+
+        if let Some(to) = *self.to {
+            query = query.bind(to);
+        }
+
+        if let Some(search) = self.search {
+            query = query.bind(format!("%{}%", escape_like(search)));
+        }
+
+        query
+    }
+}
+
+pub async fn search_tickets(
+    filters: &TicketFilters<'_>,
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    pool: &MySqlPool,
+) -> Result<(Vec<Ticket>, i64), Error> {
+    let fetch_size = fetch_size.unwrap_or(10);
+    let page = page.unwrap_or(1);
+    let offset = (page - 1) * fetch_size;
+
+    let mut connection = pool.acquire().await?;
+
+    let where_sql = filters.where_sql();
+
+    let sql = format!("SELECT COUNT(*) FROM ticket o{where_sql}");
+    let query = filters.bind(sqlx::query(&sql));
+
+    let total_count = connection.fetch_one(query).await?.try_get(0)?;
+
+    let sql = format!(
+        "SELECT o.ticket, o.round_id, o.sender, o.recipient, o.timestamp,
+            (SELECT MIN(r.timestamp) FROM ticket r WHERE {SAME_TICKET} AND o.recipient IS NULL AND r.sender <> o.sender AND r.action IN {RESPONSE_ACTIONS}) AS first_response,
+            (SELECT r.action FROM ticket r WHERE {SAME_TICKET} AND r.action IN {CLOSING_ACTIONS} ORDER BY r.timestamp DESC, r.id DESC LIMIT 1) AS closing_action,
+            (SELECT COUNT(*) FROM ticket r WHERE {SAME_TICKET} AND r.action IN ('Ticket Opened', 'Reply')) AS message_count
+        FROM ticket o{where_sql}
+        ORDER BY o.timestamp DESC, o.round_id DESC, o.ticket DESC
+        LIMIT ? OFFSET ?"
+    );
+    let query = filters
+        .bind(sqlx::query(&sql))
+        .bind(fetch_size)
+        .bind(offset);
+
+    let mut tickets = Vec::new();
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            let opened_at: NaiveDateTime = row.try_get("timestamp")?;
+            let first_response: Option<NaiveDateTime> = row.try_get("first_response")?;
+            let closing_action: Option<String> = row.try_get("closing_action")?;
+
+            tickets.push(Ticket {
+                ticket_id: row.try_get("ticket")?,
+                round_id: row.try_get("round_id")?,
+                opened_by: row.try_get("sender")?,
+                recipient: row.try_get("recipient")?,
+                opened_at,
+                first_response: first_response
+                    .map(|responded| (responded - opened_at).num_seconds()),
+                state: TicketState::from_action(closing_action.as_deref()),
+                message_count: row.try_get("message_count")?,
+                logs: Vec::new(),
+            });
+        }
+    }
+
+    if !tickets.is_empty() {
+        let sql = format!(
+            "SELECT ticket, round_id, action, message, sender, recipient, timestamp FROM ticket WHERE (round_id, ticket) IN ({}) AND action NOT IN ('Reconnected', 'Disconnected', 'Interaction') ORDER BY timestamp ASC, id ASC",
+            vec!["(?, ?)"; tickets.len()].join(", ")
+        );
+
+        let mut query = sqlx::query(&sql);
+
+        for ticket in &tickets {
+            query = query.bind(ticket.round_id).bind(ticket.ticket_id);
+        }
+
+        let mut logs: HashMap<(Option<u32>, u32), Vec<TicketLog>> = HashMap::new();
+
+        {
+            let mut rows = connection.fetch(query);
+
+            while let Some(row) = rows.next().await {
+                let row = row?;
+
+                logs.entry((row.try_get("round_id")?, row.try_get("ticket")?))
+                    .or_default()
+                    .push(TicketLog::from_row(&row)?);
+            }
+        }
+
+        for ticket in &mut tickets {
+            ticket.logs = logs
+                .remove(&(ticket.round_id, ticket.ticket_id))
+                .unwrap_or_default();
+        }
+    }
+
+    connection.close().await?;
+
+    Ok((tickets, total_count))
+}
@@ -0,0 +1,267 @@
+use chrono::NaiveDateTime;
+use rocket::futures::StreamExt as _;
+use serde::Serialize;
+use sqlx::{mysql::MySqlRow, Executor as _, MySqlPool, Row as _};
+
+use crate::{cache::Cache, config::Config};
+
+use super::{error::Error, format_offset, get_round_ids};
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineEvent {
+    RoundInitialized,
+    RoundStarted,
+    RoundEnded,
+    Population {
+        players: i64,
+    },
+    Death {
+        name: String,
+        job: String,
+        special: Option<String>,
+        last_attacker: Option<String>,
+        suicide: bool,
+    },
+    /// A citation with a fine attached.
+    Citation {
+        sender: String,
+        recipient: String,
+        crime: String,
+        fine: i32,
+    },
+    /// A crime logged without a fine.
+    Crime {
+        sender: String,
+        recipient: String,
+        crime: String,
+    },
+    TicketOpened {
+        ticket: u16,
+        sender: Option<String>,
+        recipient: Option<String>,
+    },
+    AchievementUnlocked {
+        ckey: Option<String>,
+        achievement_key: String,
+        achievement_name: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineItem {
+    /// Time since the round was initialized, formatted like the population samples of a round.
+    pub offset: String,
+    #[serde(with = "crate::serde::datetime")]
+    pub timestamp: NaiveDateTime,
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+}
+
+fn citation_event(row: &MySqlRow) -> Result<TimelineEvent, sqlx::Error> {
+    let sender = row.try_get("sender_ic")?;
+    let recipient = row.try_get("recipient")?;
+    let crime = row.try_get("crime")?;
+
+    Ok(match row.try_get::<Option<i32>, _>("fine")? {
+        Some(fine) if fine != 0 => TimelineEvent::Citation {
+            sender,
+            recipient,
+            crime,
+            fine,
+        },
+        _ => TimelineEvent::Crime {
+            sender,
+            recipient,
+            crime,
+        },
+    })
+}
+
+/// Returns `ckey` unless it belongs to a hidden player the caller may not see.
+fn visible_ckey(ckey: Option<String>, hidden: Option<String>, show_hidden: bool) -> Option<String> {
+    match hidden {
+        Some(_) if !show_hidden => None,
+        _ => ckey,
+    }
+}
+
+/// Merges everything recorded during a finished round into one chronological stream. Rounds
+/// without an end time or still running on any server are refused, and hidden ckeys are left out
+/// unless `show_hidden` is set.
+pub async fn get_round_timeline(
+    round_id: i32,
+    show_hidden: bool,
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Vec<TimelineItem>, Error> {
+    if get_round_ids(config, cache).await.contains(&round_id) {
+        return Err(Error::RoundNotFound);
+    }
+
+    let mut connection = pool.acquire().await?;
+
+    let query = sqlx::query(
+        "SELECT initialize_datetime, start_datetime, shutdown_datetime, end_datetime FROM round WHERE id = ?",
+    )
+    .bind(round_id);
+
+    let Some(round) = connection.fetch_optional(query).await? else {
+        connection.close().await?;
+        return Err(Error::RoundNotFound);
+    };
+
+    let initialize_datetime: NaiveDateTime = round.try_get("initialize_datetime")?;
+    let start_datetime: Option<NaiveDateTime> = round.try_get("start_datetime")?;
+    let shutdown_datetime: Option<NaiveDateTime> = round.try_get("shutdown_datetime")?;
+    let end_datetime: Option<NaiveDateTime> = round.try_get("end_datetime")?;
+
+    let Some(end_datetime) = end_datetime else {
+        connection.close().await?;
+        return Err(Error::RoundNotFound);
+    };
+
+    let mut events = vec![(initialize_datetime, TimelineEvent::RoundInitialized)];
+
+    if let Some(start_datetime) = start_datetime {
+        events.push((start_datetime, TimelineEvent::RoundStarted));
+    }
+
+    let query = sqlx::query(
+        "SELECT time, playercount FROM legacy_population WHERE round_id = ? ORDER BY time ASC",
+    )
+    .bind(round_id);
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            events.push((
+                row.try_get("time")?,
+                TimelineEvent::Population {
+                    players: row.try_get("playercount")?,
+                },
+            ));
+        }
+    }
+
+    let query = sqlx::query(
+        "SELECT tod, name, job, special, laname, suicide FROM death WHERE round_id = ? ORDER BY tod ASC, id ASC",
+    )
+    .bind(round_id);
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            events.push((
+                row.try_get("tod")?,
+                TimelineEvent::Death {
+                    name: row.try_get("name")?,
+                    job: row.try_get("job")?,
+                    special: row.try_get("special")?,
+                    last_attacker: row.try_get("laname")?,
+                    suicide: row.try_get("suicide")?,
+                },
+            ));
+        }
+    }
+
+    let query = sqlx::query(
+        "SELECT timestamp, sender_ic, recipient, crime, fine FROM citation WHERE round_id = ? ORDER BY timestamp ASC, id ASC",
+    )
+    .bind(round_id);
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            events.push((row.try_get("timestamp")?, citation_event(&row)?));
+        }
+    }
+
+    let sql = format!(
+        "SELECT t.timestamp, t.ticket, t.sender, t.recipient, hs.ckey AS sender_hidden, hr.ckey AS recipient_hidden FROM {0}.ticket t LEFT JOIN {1}.hid_ckeys_autocomplete hs ON hs.ckey = t.sender AND hs.valid = 1 LEFT JOIN {1}.hid_ckeys_autocomplete hr ON hr.ckey = t.recipient AND hr.valid = 1 WHERE t.round_id = ? AND t.action = 'Ticket Opened' ORDER BY t.timestamp ASC, t.id ASC",
+        config.database.game_database, config.database.api_database
+    );
+
+    let query = sqlx::query(&sql).bind(round_id);
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            events.push((
+                row.try_get("timestamp")?,
+                TimelineEvent::TicketOpened {
+                    ticket: row.try_get("ticket")?,
+                    sender: visible_ckey(
+                        row.try_get("sender")?,
+                        row.try_get("sender_hidden")?,
+                        show_hidden,
+                    ),
+                    recipient: visible_ckey(
+                        row.try_get("recipient")?,
+                        row.try_get("recipient_hidden")?,
+                        show_hidden,
+                    ),
+                },
+            ));
+        }
+    }
+
+    // Achievements aren't tied to a round, so take the ones the crew updated while it ran.
+    let finished = shutdown_datetime.unwrap_or(end_datetime);
+
+    let sql = format!(
+        "SELECT a.ckey, a.achievement_key, a.last_updated, m.achievement_name, h.ckey AS hidden FROM {0}.achievements a LEFT JOIN {0}.achievement_metadata m ON a.achievement_key = m.achievement_key LEFT JOIN {1}.hid_ckeys_autocomplete h ON h.ckey = a.ckey AND h.valid = 1 WHERE a.last_updated BETWEEN ? AND ? AND a.ckey IN (SELECT ckey FROM {0}.manifest WHERE round_id = ?) ORDER BY a.last_updated ASC, a.ckey ASC",
+        config.database.game_database, config.database.api_database
+    );
+
+    let query = sqlx::query(&sql)
+        .bind(initialize_datetime)
+        .bind(finished)
+        .bind(round_id);
+
+    {
+        let mut rows = connection.fetch(query);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+
+            events.push((
+                row.try_get("last_updated")?,
+                TimelineEvent::AchievementUnlocked {
+                    ckey: visible_ckey(row.try_get("ckey")?, row.try_get("hidden")?, show_hidden),
+                    achievement_key: row.try_get("achievement_key")?,
+                    achievement_name: row.try_get("achievement_name")?,
+                },
+            ));
+        }
+    }
+
+    events.push((end_datetime, TimelineEvent::RoundEnded));
+
+    connection.close().await?;
+
+    // Stable, so events at the same second keep the order they were gathered in.
+    events.sort_by_key(|(timestamp, _)| *timestamp);
+
+    Ok(events
+        .into_iter()
+        .map(|(timestamp, event)| TimelineItem {
+            offset: format_offset(timestamp, Some(initialize_datetime)),
+            timestamp,
+            event,
+        })
+        .collect())
+}
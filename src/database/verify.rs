@@ -2,14 +2,18 @@ use rand::Rng as _;
 use regex::Regex;
 use sqlx::{pool::PoolConnection, Executor as _, MySql, MySqlPool, Row as _};
 
-use crate::http::discord::{self, User};
+use crate::{
+    cache::Cache,
+    ckey::Ckey,
+    http::discord::{self, User},
+};
 
 use super::{error::Error, player_exists};
 
 pub async fn verify_discord(
     discord_id: &str,
     one_time_token: Option<&str>,
-    ckey: Option<&str>,
+    ckey: Option<&Ckey>,
     skip_ckey: Option<bool>,
     pool: &MySqlPool,
 ) -> Result<Option<String>, Error> {
@@ -63,7 +67,7 @@ pub async fn verify_discord(
             "INSERT INTO discord_links (discord_id, ckey, one_time_token, valid) VALUES (?, ?, ?, 1)",
         )
         .bind(discord_id)
-        .bind(ckey.to_lowercase())
+        .bind(ckey)
         .bind(token);
 
         connection.execute(query).await?;
@@ -77,7 +81,7 @@ pub async fn verify_discord(
 
 pub async fn unverify_discord(
     discord_id: Option<&str>,
-    ckey: Option<&str>,
+    ckey: Option<&Ckey>,
     pool: &MySqlPool,
 ) -> Result<String, Error> {
     let mut connection = pool.acquire().await?;
@@ -96,9 +100,8 @@ pub async fn unverify_discord(
     } else if let Some(ckey) = ckey {
         let discord_id = discord_id_by_ckey(ckey, &mut connection).await?;
 
-        let query =
-            sqlx::query("UPDATE discord_links SET valid = 0 WHERE LOWER(ckey) = ? AND valid = 1")
-                .bind(ckey.to_lowercase());
+        let query = sqlx::query("UPDATE discord_links SET valid = 0 WHERE ckey = ? AND valid = 1")
+            .bind(ckey);
 
         connection.execute(query).await?;
         connection.close().await?;
@@ -124,12 +127,11 @@ pub async fn ckey_by_discord_id(
 }
 
 pub async fn discord_id_by_ckey(
-    ckey: &str,
+    ckey: &Ckey,
     connection: &mut PoolConnection<MySql>,
 ) -> Result<i64, Error> {
     let query =
-        sqlx::query("SELECT discord_id FROM discord_links WHERE LOWER(ckey) = ? AND valid = 1")
-            .bind(ckey.to_lowercase());
+        sqlx::query("SELECT discord_id FROM discord_links WHERE ckey = ? AND valid = 1").bind(ckey);
 
     if let Ok(row) = connection.fetch_one(query).await {
         return Ok(row.try_get("discord_id")?);
@@ -163,8 +165,9 @@ async fn discord_id_by_token(
 }
 
 pub async fn fetch_discord_by_ckey(
-    ckey: &str,
+    ckey: &Ckey,
     discord_token: &str,
+    cache: &Cache,
     pool: &MySqlPool,
 ) -> Result<User, Error> {
     let mut connection = pool.acquire().await?;
@@ -173,7 +176,18 @@ pub async fn fetch_discord_by_ckey(
 
     connection.close().await?;
 
-    let user = discord::get_user(discord_id, discord_token).await?;
+    fetch_discord_user(discord_id, discord_token, cache).await
+}
+
+pub async fn fetch_discord_user(
+    discord_id: i64,
+    discord_token: &str,
+    cache: &Cache,
+) -> Result<User, Error> {
+    let user = cache
+        .discord_users
+        .get_or_try_load(discord_id, || discord::get_user(discord_id, discord_token))
+        .await?;
 
     Ok(user)
 }
@@ -0,0 +1,105 @@
+use std::{fmt, ops::Deref};
+
+use chrono::NaiveDate;
+use rocket::form::{self, FromFormField, ValueField};
+use sqlx::{encode::IsNull, mysql::MySqlTypeInfo, Encode, MySql, Type};
+
+const FORMAT: &str = "%Y-%m-%d";
+
+/// A calendar day passed as `YYYY-MM-DD`. Bound to a DATETIME column it compares as midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(NaiveDate);
+
+impl Date {
+    pub fn parse(value: &str) -> Option<Self> {
+        NaiveDate::parse_from_str(value, FORMAT).ok().map(Self)
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(date: NaiveDate) -> Self {
+        Self(date)
+    }
+}
+
+impl Deref for Date {
+    type Target = NaiveDate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format(FORMAT))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromFormField<'r> for Date {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        Ok(Self::parse(field.value)
+            .ok_or_else(|| form::Error::validation("expected a date formatted as YYYY-MM-DD"))?)
+    }
+}
+
+impl Type<MySql> for Date {
+    fn type_info() -> MySqlTypeInfo {
+        <NaiveDate as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <NaiveDate as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for Date {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        <NaiveDate as Encode<MySql>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+/// An optional [`Date`] parameter. Rocket parses a malformed `Option<T>` as `None`, which would
+/// quietly drop the bound, so this fails the form instead and lets the route answer 400.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OptionalDate(pub Option<Date>);
+
+impl Deref for OptionalDate {
+    type Target = Option<Date>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromFormField<'r> for OptionalDate {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        Date::from_value(field).map(|date| Self(Some(date)))
+    }
+
+    fn default() -> Option<Self> {
+        Some(Self(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_calendar_days() {
+        let date = Date::parse("2024-01-31").unwrap();
+        assert_eq!(*date, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+        assert_eq!(date.to_string(), "2024-01-31");
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        assert!(Date::parse("").is_none());
+        assert!(Date::parse("yesterday").is_none());
+        assert!(Date::parse("2024-02-30").is_none());
+        assert!(Date::parse("2024-01-01'; DROP TABLE round; --").is_none());
+    }
+}
@@ -0,0 +1,52 @@
+use rocket::{catch, catchers, http::Status, Build, Config as RocketConfig, Request, Rocket};
+use thiserror::Error;
+
+use crate::{cache::Cache, config::Config, cors::cors, database::Database};
+
+pub mod byond;
+pub mod cache;
+pub mod ckey;
+pub mod config;
+mod cors;
+pub mod database;
+pub mod date;
+pub mod http;
+mod routes;
+mod serde;
+#[cfg(test)]
+mod tests;
+
+#[allow(clippy::result_large_err)]
+pub fn build(config: Config, database: Database, cache: Cache) -> Result<Rocket<Build>, Error> {
+    let provider = RocketConfig {
+        address: config.address,
+        port: config.port,
+        cli_colors: config.cli_colors,
+        log_level: config.log_level,
+        ip_header: config.ip_header.clone().map(Into::into),
+        ..Default::default()
+    };
+
+    let rocket = rocket::custom(provider)
+        .attach(cors()?)
+        .manage(config)
+        .manage(database)
+        .manage(cache)
+        .register("/", catchers![empty_catcher]);
+
+    Ok(routes::mount(rocket))
+}
+
+#[catch(default)]
+fn empty_catcher(_: Status, _: &Request) {}
+
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub enum Error {
+    Cache(#[from] cache::Error),
+    Config(#[from] config::Error),
+    Cors(#[from] rocket_cors::Error),
+    Rocket(#[from] rocket::Error),
+    Sqlx(#[from] sqlx::Error),
+    SetGlobalDefault(#[from] tracing::subscriber::SetGlobalDefaultError),
+}
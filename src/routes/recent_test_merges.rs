@@ -1,41 +1,42 @@
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::time::Duration;
 
-use once_cell::sync::Lazy;
 use rocket::{get, http::Status, serde::json::Json, State};
-use tokio::sync::RwLock;
 
 use crate::{
-    database::{get_recent_test_merges, TestMerge},
+    cache::Cache,
+    database::{error::Error, get_recent_test_merges, TestMerge},
     Database,
 };
 
-type TestMergesCache = Option<(Instant, Vec<TestMerge>)>;
+use super::v2::RateLimit;
 
-static LAST_RECENT_TEST_MERGES: Lazy<Arc<RwLock<TestMergesCache>>> =
-    Lazy::new(|| Arc::new(RwLock::new(None)));
+const TEST_MERGES_TTL: Duration = Duration::from_secs(600);
 
 #[get("/recent-test-merges.json")]
 pub async fn recent_test_merges(
     database: &State<Database>,
+    cache: &State<Cache>,
+    _rate_limit: RateLimit,
 ) -> Result<Json<Vec<TestMerge>>, Status> {
-    {
-        let recent_test_merges = LAST_RECENT_TEST_MERGES.read().await;
-        if let Some((last_update, test_merges)) = &*recent_test_merges {
-            if last_update.elapsed() < Duration::from_secs(600) {
-                return Ok(Json(test_merges.clone()));
+    let test_merges = cache
+        .recent_test_merges
+        .get_or_try_load((), || async {
+            if let Some(test_merges) = cache.get_shared("recent_test_merges").await {
+                return Ok(test_merges);
             }
-        }
-    }
 
-    let Ok(test_merges) = get_recent_test_merges(&database.pool).await else {
+            let test_merges = get_recent_test_merges(&database.pool).await?;
+            cache
+                .set_shared("recent_test_merges", &test_merges, TEST_MERGES_TTL)
+                .await;
+
+            Ok::<_, Error>(test_merges)
+        })
+        .await;
+
+    let Ok(test_merges) = test_merges else {
         return Err(Status::InternalServerError);
     };
 
-    let mut recent_test_merges = LAST_RECENT_TEST_MERGES.write().await;
-    *recent_test_merges = Some((Instant::now(), test_merges.clone()));
-
     Ok(Json(test_merges))
 }
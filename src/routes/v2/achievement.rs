@@ -0,0 +1,61 @@
+use rocket::{get, http::Status, State};
+
+use crate::{
+    cache::Cache,
+    database::{error::Error, *},
+    Database,
+};
+
+use super::{common::ApiKey, Json};
+
+const MAX_ACTIVE_DAYS: u32 = 3650;
+const DEFAULT_LIMIT: u32 = 15;
+const MAX_LIMIT: u32 = 100;
+
+#[get("/achievements?<active_days>")]
+pub async fn index(
+    active_days: Option<u32>,
+    database: &State<Database>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<AchievementRarity>>, Status> {
+    let active_days = active_days
+        .unwrap_or(ACTIVE_PLAYER_DAYS)
+        .clamp(1, MAX_ACTIVE_DAYS);
+
+    match get_achievement_rarities(active_days, cache, &database.pool).await {
+        Ok(achievements) => Ok(Json::Ok(achievements)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/achievements/top?<achievement_key>&<limit>")]
+pub async fn top(
+    achievement_key: &str,
+    limit: Option<u32>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<AchievementScore>>, Status> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    match get_achievement_leaderboard(achievement_key, limit, &database.pool).await {
+        Ok(scores) => Ok(Json::Ok(scores)),
+        Err(Error::AchievementNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/achievements/recent?<achievement_type>&<limit>")]
+pub async fn recent(
+    achievement_type: Option<&str>,
+    limit: Option<u32>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<AchievementUnlock>>, Status> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    match get_recent_unlocks(achievement_type, limit, &database.pool).await {
+        Ok(unlocks) => Ok(Json::Ok(unlocks)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -1,30 +1,48 @@
 use rocket::{get, http::Status, post, State};
+use serde_json::{json, Value};
 
-use crate::{config::Config, database::*, Database};
+use crate::{ckey::Ckey, config::Config, database::*, Database};
 
 use super::{common::ApiKey, Json};
 
-#[get("/autocomplete/job?<job>")]
+#[get("/autocomplete/job?<job>&<departments>")]
 pub async fn job(
     job: &str,
+    departments: Option<bool>,
     database: &State<Database>,
+    config: &State<Config>,
     _api_key: ApiKey,
-) -> Result<Json<Vec<String>>, Status> {
+) -> Result<Json<Value>, Status> {
     let Ok(jobs) = get_jobs(job, &database.pool).await else {
         return Err(Status::InternalServerError);
     };
 
-    Ok(Json::Ok(jobs))
+    if !departments.unwrap_or(false) {
+        return Ok(Json::Ok(json!(jobs)));
+    }
+
+    let query = job.to_lowercase();
+    let departments: Vec<&String> = config
+        .jobs
+        .departments
+        .keys()
+        .filter(|department| department.to_lowercase().contains(&query))
+        .collect();
+
+    Ok(Json::Ok(json!({
+        "departments": departments,
+        "jobs": jobs
+    })))
 }
 
 #[get("/autocomplete/ckey?<ckey>")]
 pub async fn ckey(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     config: &State<Config>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<String>>, Status> {
-    let Ok(ckeys) = get_ckeys(ckey, &database.pool, config).await else {
+    let Ok(ckeys) = get_ckeys(&ckey, &database.pool, config).await else {
         return Err(Status::InternalServerError);
     };
 
@@ -46,13 +64,13 @@ pub async fn ic_name(
 
 #[post("/autocomplete/ckey/hide?<ckey>&<hid_by>")]
 pub async fn hide_ckey_autocomplete(
-    ckey: &str,
+    ckey: Ckey,
     hid_by: i64,
     database: &State<Database>,
     config: &State<Config>,
     _api_key: ApiKey,
 ) -> Result<Json<bool>, Status> {
-    match hide_ckey(ckey, hid_by, &database.pool, config).await {
+    match hide_ckey(&ckey, hid_by, &database.pool, config).await {
         Ok(true) => Ok(Json::Ok(true)),
         Ok(false) => Err(Status::Conflict),
         Err(_) => Err(Status::InternalServerError),
@@ -61,13 +79,13 @@ pub async fn hide_ckey_autocomplete(
 
 #[post("/autocomplete/ckey/unhide?<ckey>&<unhid_by>")]
 pub async fn unhide_ckey_autocomplete(
-    ckey: &str,
+    ckey: Ckey,
     unhid_by: i64,
     database: &State<Database>,
     config: &State<Config>,
     _api_key: ApiKey,
 ) -> Result<Json<bool>, Status> {
-    match unhide_ckey(ckey, unhid_by, &database.pool, config).await {
+    match unhide_ckey(&ckey, unhid_by, &database.pool, config).await {
         Ok(true) => Ok(Json::Ok(true)),
         Ok(false) => Err(Status::Conflict),
         Err(_) => Err(Status::InternalServerError),
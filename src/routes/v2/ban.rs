@@ -1,6 +1,12 @@
-use rocket::{get, http::Status, State};
+use rocket::{form, get, http::Status, State};
+use serde_json::{json, Value};
 
-use crate::{database::*, Database};
+use crate::{
+    ckey::Ckey,
+    config::Config,
+    database::{error::Error, *},
+    Database,
+};
 
 use super::{common::ApiKey, Json};
 
@@ -16,3 +22,48 @@ pub async fn index(
         Err(_) => Err(Status::InternalServerError),
     }
 }
+
+#[get("/bans?<fetch_size>&<page>&<filters..>")]
+pub async fn search(
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    filters: form::Result<'_, BanFilters<'_>>,
+    database: &State<Database>,
+    config: &State<Config>,
+    _api_key: ApiKey,
+) -> Result<Json<Value>, Status> {
+    let Ok(filters) = filters else {
+        return Err(Status::BadRequest);
+    };
+
+    match get_bans(&filters, fetch_size, page, config, &database.pool).await {
+        Ok((bans, total_count)) => Ok(Json::Ok(json!({
+            "data": bans,
+            "total_count": total_count
+        }))),
+        Err(Error::ServerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/ban/check?<ckey>&<ip>&<cid>&<role>")]
+pub async fn check(
+    ckey: Option<Ckey>,
+    ip: Option<&str>,
+    cid: Option<&str>,
+    role: Option<&str>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Value>, Status> {
+    if ckey.is_none() && ip.is_none() && cid.is_none() {
+        return Err(Status::BadRequest);
+    }
+
+    match check_ban(ckey.as_ref(), ip, cid, role, &database.pool).await {
+        Ok(bans) => Ok(Json::Ok(json!({
+            "banned": !bans.is_empty(),
+            "bans": bans
+        }))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
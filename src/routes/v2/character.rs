@@ -0,0 +1,27 @@
+use rocket::{get, http::Status, State};
+
+use crate::{
+    cache::Cache,
+    ckey::Ckey,
+    config::Config,
+    database::{error::Error, *},
+    Database,
+};
+
+use super::{common::ApiKey, Json};
+
+#[get("/character?<name>&<ckey>")]
+pub async fn index(
+    name: &str,
+    ckey: Option<Ckey>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Character>, Status> {
+    match get_character(name, ckey.as_ref(), &database.pool, config, cache).await {
+        Ok(character) => Ok(Json::Ok(character)),
+        Err(Error::CharacterNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
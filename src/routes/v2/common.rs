@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::{io::Cursor, time::Duration};
 
 use rocket::{
     http::{ContentType, Status},
@@ -8,7 +8,7 @@ use rocket::{
 };
 use serde::Serialize;
 
-use crate::config::Config;
+use crate::{cache::Cache, config::Config};
 
 #[derive(Debug, Serialize)]
 pub enum Json<R> {
@@ -35,7 +35,17 @@ impl<R: Serialize> Responder<'_, 'static> for Json<R> {
     }
 }
 
-pub struct ApiKey;
+/// Which of the configured secrets the request was authenticated with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope {
+    Full,
+    Dev,
+    Exposed,
+}
+
+pub struct ApiKey {
+    pub scope: KeyScope,
+}
 
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for ApiKey {
@@ -47,13 +57,17 @@ impl<'r> FromRequest<'r> for ApiKey {
         };
 
         if request.headers().get_one("X-API-KEY") == Some(&config.secret) {
-            return Outcome::Success(ApiKey);
+            return Outcome::Success(ApiKey {
+                scope: KeyScope::Full,
+            });
         }
 
         if request.headers().get_one("X-DEV-KEY") == Some(&config.dev_secret) {
             if let Some(route) = request.route() {
                 if config.dev_routes.contains(route.uri.origin.path().as_str()) {
-                    return Outcome::Success(ApiKey);
+                    return Outcome::Success(ApiKey {
+                        scope: KeyScope::Dev,
+                    });
                 }
             }
         }
@@ -64,7 +78,9 @@ impl<'r> FromRequest<'r> for ApiKey {
                     .exposed_routes
                     .contains(route.uri.origin.path().as_str())
                 {
-                    return Outcome::Success(ApiKey);
+                    return Outcome::Success(ApiKey {
+                        scope: KeyScope::Exposed,
+                    });
                 }
             }
         }
@@ -72,3 +88,37 @@ impl<'r> FromRequest<'r> for ApiKey {
         Outcome::Error((Status::Unauthorized, ()))
     }
 }
+
+/// Limits each client address to `rate_limit` requests per minute, if one is configured.
+pub struct RateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let (Some(config), Some(cache)) = (
+            request.rocket().state::<Config>(),
+            request.rocket().state::<Cache>(),
+        ) else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+
+        let Some(rate_limit) = config.rate_limit else {
+            return Outcome::Success(RateLimit);
+        };
+
+        let Some(ip) = request.client_ip() else {
+            return Outcome::Success(RateLimit);
+        };
+
+        match cache
+            .increment(&format!("rate_limit:{ip}"), Duration::from_secs(60))
+            .await
+        {
+            Ok(count) if count > rate_limit => Outcome::Error((Status::TooManyRequests, ())),
+            // a broken backend should not take the public routes down with it
+            _ => Outcome::Success(RateLimit),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use rocket::{get, http::Status, State};
 
 use crate::{
+    cache::Cache,
     config::Config,
     http::{
         self,
@@ -14,13 +15,19 @@ use super::{common::ApiKey, Json};
 pub async fn user(
     discord_id: &str,
     config: &State<Config>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<User>, Status> {
     let Ok(id) = discord_id.parse::<i64>() else {
         return Err(Status::BadRequest);
     };
 
-    match discord::get_user(id, &config.discord.token).await {
+    let user = cache
+        .discord_users
+        .get_or_try_load(id, || discord::get_user(id, &config.discord.token))
+        .await;
+
+    match user {
         Ok(user) => Ok(Json::Ok(user)),
         Err(http::Error::Discord(code)) => match code {
             10013 => Err(Status::NotFound),
@@ -1,18 +1,29 @@
-use rocket::{get, http::Status, State};
+use rocket::{form, get, http::Status, State};
 use serde_json::{json, Value};
 
-use crate::{database::*, Config, Database};
+use crate::{cache::Cache, database::*, date::OptionalDate, Config, Database};
 
-use super::{common::ApiKey, Json};
+use super::{
+    common::{ApiKey, KeyScope},
+    Json,
+};
 
 #[get("/events/overview?<limit>")]
 pub async fn overview(
     limit: Option<i32>,
     config: &State<Config>,
+    cache: &State<Cache>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<Overview>>, Status> {
-    match get_overview(limit.unwrap_or(1), config, &database.pool).await {
+    let limit = limit.unwrap_or(1);
+
+    let overview = cache
+        .overview
+        .get_or_try_load(limit, || get_overview(limit, config, cache, &database.pool))
+        .await;
+
+    match overview {
         Ok(overview) => Ok(Json::Ok(overview)),
         Err(_) => Err(Status::InternalServerError),
     }
@@ -23,10 +34,11 @@ pub async fn deaths(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &State<Config>,
+    cache: &State<Cache>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_deaths(fetch_size, page, config, &database.pool).await {
+    match get_deaths(fetch_size, page, config, cache, &database.pool).await {
         Ok((deaths, total_count)) => Ok(Json::Ok(json!({
             "data": deaths,
             "total_count": total_count
@@ -35,15 +47,46 @@ pub async fn deaths(
     }
 }
 
+#[get("/events/killers?<since>&<limit>")]
+pub async fn killers(
+    since: form::Result<'_, OptionalDate>,
+    limit: Option<u32>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    database: &State<Database>,
+    api_key: ApiKey,
+) -> Result<Json<Vec<Killer>>, Status> {
+    if !config.killers_leaderboard {
+        return Err(Status::NotFound);
+    }
+
+    // last attacker ckeys are staff only, whatever routes the other keys are allowed
+    if api_key.scope != KeyScope::Full {
+        return Err(Status::Forbidden);
+    }
+
+    let Ok(OptionalDate(since)) = since else {
+        return Err(Status::BadRequest);
+    };
+
+    let limit = limit.unwrap_or(15).clamp(1, 100);
+
+    match get_top_killers(since, limit, config, cache, &database.pool).await {
+        Ok(killers) => Ok(Json::Ok(killers)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
 #[get("/events/citations?<fetch_size>&<page>")]
 pub async fn citations(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &State<Config>,
+    cache: &State<Cache>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_citations(fetch_size, page, config, &database.pool).await {
+    match get_citations(fetch_size, page, config, cache, &database.pool).await {
         Ok((citations, total_count)) => Ok(Json::Ok(json!({
             "data": citations,
             "total_count": total_count
@@ -57,10 +100,11 @@ pub async fn crimes(
     fetch_size: Option<i32>,
     page: Option<i32>,
     config: &State<Config>,
+    cache: &State<Cache>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_crimes(fetch_size, page, config, &database.pool).await {
+    match get_crimes(fetch_size, page, config, cache, &database.pool).await {
         Ok((crimes, total_count)) => Ok(Json::Ok(json!({
             "data": crimes,
             "total_count": total_count
@@ -1,15 +1,21 @@
 use rocket::{routes, Build, Rocket};
 
+mod achievement;
 mod autocomplete;
 mod ban;
 mod byond;
+mod character;
 mod common;
 mod discord;
 mod events;
 mod patreon;
 mod player;
+mod profile;
+mod roletime;
 mod round;
 mod server;
+mod stats;
+mod ticket;
 mod verify;
 
 pub use common::*;
@@ -25,7 +31,13 @@ pub fn mount(rocket: Rocket<Build>) -> Rocket<Build> {
             player::characters,
             player::roletime,
             player::activity,
+            player::activity_heatmap,
+            player::deaths,
+            player::death_stats,
+            player::antagonists,
             player::top,
+            player::department_roletime,
+            player::roletime_history,
             player::discord,
             player::achievements,
             player::fav_character,
@@ -41,8 +53,15 @@ pub fn mount(rocket: Rocket<Build>) -> Rocket<Build> {
             player::acceptfriend,
             player::declinefriend,
             player::lookup,
+            player::lookup_graph,
+            profile::index,
+            roletime::top,
             round::index,
             round::rounds,
+            round::feedback,
+            round::feedback_series,
+            round::manifest,
+            round::timeline,
             server::index,
             verify::index,
             verify::unverify,
@@ -58,7 +77,18 @@ pub fn mount(rocket: Rocket<Build>) -> Rocket<Build> {
             events::citations,
             events::crimes,
             events::deaths,
+            events::killers,
+            achievement::index,
+            achievement::top,
+            achievement::recent,
             ban::index,
+            ban::search,
+            ban::check,
+            character::index,
+            stats::moderation,
+            stats::maps,
+            stats::antagonists,
+            ticket::index,
         ],
     )
 }
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use rocket::{get, http::Status, State};
 use serde_json::{json, Value};
 use sqlx::MySqlPool;
 
 use crate::{
+    cache::Cache,
+    ckey::Ckey,
     config::{self, Config},
     database::{error::Error, *},
     http::{
@@ -16,19 +20,23 @@ use super::{common::ApiKey, Json};
 
 #[get("/patreon?<ckey>")]
 pub async fn index(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     config: &State<Config>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    let Ok(patron) = is_patron(ckey, &database.pool, &config.discord).await else {
+    let Ok(patron) = is_patron(&ckey, &database.pool, &config.discord).await else {
         return Err(Status::InternalServerError);
     };
 
     Ok(Json::Ok(json!({ "patron": patron })))
 }
 
-async fn is_patron(ckey: &str, pool: &MySqlPool, discord: &config::Discord) -> Result<bool, Error> {
+pub(super) async fn is_patron(
+    ckey: &Ckey,
+    pool: &MySqlPool,
+    discord: &config::Discord,
+) -> Result<bool, Error> {
     let mut connection = pool.acquire().await?;
 
     let Ok(discord_id) = discord_id_by_ckey(ckey, &mut connection).await else {
@@ -37,6 +45,13 @@ async fn is_patron(ckey: &str, pool: &MySqlPool, discord: &config::Discord) -> R
 
     connection.close().await?;
 
+    has_patreon_role(discord_id, discord).await
+}
+
+pub(super) async fn has_patreon_role(
+    discord_id: i64,
+    discord: &config::Discord,
+) -> Result<bool, Error> {
     let member = match get_guild_member(discord.guild, discord_id, &discord.token).await {
         Ok(member) => member,
         Err(http::Error::Discord(code)) => match code {
@@ -49,13 +64,30 @@ async fn is_patron(ckey: &str, pool: &MySqlPool, discord: &config::Discord) -> R
     Ok(member.roles.contains(&discord.patreon_role.to_string()))
 }
 
+const PATRONS_TTL: Duration = Duration::from_secs(300);
+
 #[get("/patreon/patrons")]
 pub async fn patrons(
     database: &State<Database>,
     config: &State<Config>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    let Ok(patrons) = get_patrons(&database.pool, &config.discord).await else {
+    let patrons = cache
+        .patrons
+        .get_or_try_load((), || async {
+            if let Some(patrons) = cache.get_shared("patrons").await {
+                return Ok(patrons);
+            }
+
+            let patrons = get_patrons(&database.pool, &config.discord).await?;
+            cache.set_shared("patrons", &patrons, PATRONS_TTL).await;
+
+            Ok::<_, Error>(patrons)
+        })
+        .await;
+
+    let Ok(patrons) = patrons else {
         return Err(Status::InternalServerError);
     };
 
@@ -1,9 +1,12 @@
-use rocket::{get, http::Status, post, State};
+use rocket::{form, get, http::Status, post, State};
 use serde_json::{json, Value};
 
 use crate::{
+    cache::Cache,
+    ckey::Ckey,
     config::Config,
     database::{error::Error, *},
+    date::OptionalDate,
     Database,
 };
 
@@ -11,11 +14,11 @@ use super::{common::ApiKey, Json};
 
 #[get("/player?<ckey>")]
 pub async fn index(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Player>, Status> {
-    match get_player(ckey, &database.pool).await {
+    match get_player(&ckey, &database.pool).await {
         Ok(player) => Ok(Json::Ok(player)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -24,13 +27,13 @@ pub async fn index(
 
 #[get("/player/ban?<ckey>&<permanent>&<since>")]
 pub async fn ban(
-    ckey: &str,
+    ckey: Ckey,
     permanent: Option<bool>,
     since: Option<&str>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<Ban>>, Status> {
-    match get_ban(ckey, permanent.unwrap_or(false), since, &database.pool).await {
+    match get_ban(&ckey, permanent.unwrap_or(false), since, &database.pool).await {
         Ok(bans) => Ok(Json::Ok(bans)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -39,11 +42,11 @@ pub async fn ban(
 
 #[get("/player/characters?<ckey>")]
 pub async fn characters(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<(String, i64)>>, Status> {
-    match get_characters(ckey, &database.pool).await {
+    match get_characters(&ckey, &database.pool).await {
         Ok(characters) => Ok(Json::Ok(characters)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -52,11 +55,11 @@ pub async fn characters(
 
 #[get("/player/roletime?<ckey>")]
 pub async fn roletime(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<PlayerRoletime>>, Status> {
-    match get_roletime(ckey, &database.pool).await {
+    match get_roletime(&ckey, &database.pool).await {
         Ok(roletimes) => Ok(Json::Ok(roletimes)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -67,34 +70,181 @@ pub async fn roletime(
 pub async fn top(
     job: &str,
     database: &State<Database>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<JobRoletime>>, Status> {
-    let Ok(roletimes) = get_top_roletime(job, &database.pool).await else {
+    let roletimes = cache
+        .top_roletime
+        .get_or_try_load(job.to_lowercase(), || {
+            get_top_roletime(job, 15, &database.pool)
+        })
+        .await;
+
+    let Ok(roletimes) = roletimes else {
         return Err(Status::InternalServerError);
     };
 
     Ok(Json::Ok(roletimes))
 }
 
-#[get("/player/activity?<ckey>")]
+#[get("/player/roletime/departments?<ckey>")]
+pub async fn department_roletime(
+    ckey: Ckey,
+    database: &State<Database>,
+    config: &State<Config>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<DepartmentRoletime>>, Status> {
+    match get_department_roletime(&ckey, &config.jobs, &database.pool).await {
+        Ok(departments) => Ok(Json::Ok(departments)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/player/roletime/history?<ckey>&<job>&<from>&<to>&<bucket>")]
+pub async fn roletime_history(
+    ckey: Ckey,
+    job: Option<&str>,
+    from: form::Result<'_, OptionalDate>,
+    to: form::Result<'_, OptionalDate>,
+    bucket: Option<Bucket>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<(String, i64)>>, Status> {
+    let (Ok(from), Ok(to)) = (from, to) else {
+        return Err(Status::BadRequest);
+    };
+
+    let bucket = bucket.unwrap_or(Bucket::Week);
+
+    match get_roletime_history(&ckey, job, *from, *to, bucket, &database.pool).await {
+        Ok(history) => Ok(Json::Ok(history)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/player/activity?<ckey>&<days>")]
 pub async fn activity(
-    ckey: &str,
+    ckey: Ckey,
+    days: Option<u32>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<(String, i64)>>, Status> {
-    match get_activity(ckey, &database.pool).await {
+    let days = days
+        .unwrap_or(DEFAULT_ACTIVITY_DAYS)
+        .clamp(1, MAX_ACTIVITY_DAYS);
+
+    match get_activity(&ckey, days, &database.pool).await {
         Ok(activity) => Ok(Json::Ok(activity)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
     }
 }
 
+#[get("/player/activity/heatmap?<ckey>&<days>&<timezone>")]
+pub async fn activity_heatmap(
+    ckey: Ckey,
+    days: Option<u32>,
+    timezone: Option<&str>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<ActivityHeatmap>, Status> {
+    let days = days
+        .unwrap_or(DEFAULT_ACTIVITY_DAYS)
+        .clamp(1, MAX_ACTIVITY_DAYS);
+    let timezone = timezone.unwrap_or("+00:00");
+
+    if !is_utc_offset(timezone) {
+        return Err(Status::BadRequest);
+    }
+
+    match get_activity_heatmap(&ckey, days, timezone, &database.pool).await {
+        Ok(heatmap) => Ok(Json::Ok(heatmap)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// Days of activity shown when none are asked for, including on the player profile.
+pub(super) const DEFAULT_ACTIVITY_DAYS: u32 = 180;
+const MAX_ACTIVITY_DAYS: u32 = 3650;
+
+/// Checks for a `+HH:MM` or `-HH:MM` offset between -12:00 and +14:00, as accepted by `CONVERT_TZ`.
+fn is_utc_offset(timezone: &str) -> bool {
+    let bytes = timezone.as_bytes();
+
+    let [sign, h1, h2, b':', m1, m2] = *bytes else {
+        return false;
+    };
+
+    if !matches!(sign, b'+' | b'-') || ![h1, h2, m1, m2].iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+
+    let hours = (h1 - b'0') * 10 + (h2 - b'0');
+    let minutes = (m1 - b'0') * 10 + (m2 - b'0');
+
+    let max_hours = if sign == b'+' { 14 } else { 12 };
+
+    minutes < 60 && (hours < max_hours || (hours == max_hours && minutes == 0))
+}
+
+#[get("/player/deaths?<ckey>&<fetch_size>&<page>")]
+pub async fn deaths(
+    ckey: Ckey,
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Value>, Status> {
+    match get_player_deaths(&ckey, fetch_size, page, config, cache, &database.pool).await {
+        Ok((deaths, total_count)) => Ok(Json::Ok(json!({
+            "data": deaths,
+            "total_count": total_count
+        }))),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/player/deaths/stats?<ckey>")]
+pub async fn death_stats(
+    ckey: Ckey,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<DeathStats>, Status> {
+    match get_player_death_stats(&ckey, config, cache, &database.pool).await {
+        Ok(stats) => Ok(Json::Ok(stats)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/player/antagonists?<ckey>")]
+pub async fn antagonists(
+    ckey: Ckey,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<PlayerAntagonists>, Status> {
+    match get_player_antagonists(&ckey, &database.pool).await {
+        Ok(antagonists) => Ok(Json::Ok(antagonists)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
 #[get("/player/discord?<ckey>&<discord_id>")]
 pub async fn discord(
-    ckey: Option<&str>,
+    ckey: Option<Ckey>,
     discord_id: Option<&str>,
     database: &State<Database>,
     config: &State<Config>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
     if ckey.is_some() ^ discord_id.is_none() {
@@ -102,7 +252,9 @@ pub async fn discord(
     }
 
     if let Some(ckey) = ckey {
-        return match fetch_discord_by_ckey(ckey, &config.discord.token, &database.pool).await {
+        return match fetch_discord_by_ckey(&ckey, &config.discord.token, cache, &database.pool)
+            .await
+        {
             Ok(user) => Ok(Json::Ok(json!(user))),
             Err(Error::PlayerNotFound) => Err(Status::NotFound),
             Err(Error::NotLinked) => Err(Status::Conflict),
@@ -121,12 +273,13 @@ pub async fn discord(
 
 #[get("/player/achievements?<ckey>&<achievement_type>")]
 pub async fn achievements(
-    ckey: &str,
+    ckey: Ckey,
     achievement_type: Option<&str>,
     database: &State<Database>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_achievements(ckey, achievement_type, &database.pool).await {
+    match get_achievements(&ckey, achievement_type, cache, &database.pool).await {
         Ok(achievements) => Ok(Json::Ok(json!(achievements))),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -135,11 +288,11 @@ pub async fn achievements(
 
 #[get("/player/favorite_character?<ckey>")]
 pub async fn fav_character(
-    ckey: &str,
+    ckey: Ckey,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<(String, String)>, Status> {
-    match get_favorite_character(ckey, &database.pool).await {
+    match get_favorite_character(&ckey, &database.pool).await {
         Ok(data) => Ok(Json::Ok(data)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -148,13 +301,13 @@ pub async fn fav_character(
 
 #[get("/player/tickets?<ckey>&<fetch_size>&<page>")]
 pub async fn tickets(
-    ckey: &str,
+    ckey: Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_tickets(ckey, fetch_size, page, &database.pool).await {
+    match get_tickets(&ckey, fetch_size, page, &database.pool).await {
         Ok((tickets, total_count)) => Ok(Json::Ok(json!({
             "data": tickets,
             "total_count": total_count
@@ -166,13 +319,13 @@ pub async fn tickets(
 
 #[get("/player/messages?<ckey>&<fetch_size>&<page>")]
 pub async fn messages(
-    ckey: &str,
+    ckey: Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_messages(ckey, fetch_size, page, &database.pool).await {
+    match get_messages(&ckey, fetch_size, page, &database.pool).await {
         Ok((messages, total_count)) => Ok(Json::Ok(json!({
             "data": messages,
             "total_count": total_count
@@ -184,13 +337,13 @@ pub async fn messages(
 
 #[get("/player/notes?<ckey>&<fetch_size>&<page>")]
 pub async fn notes(
-    ckey: &str,
+    ckey: Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_notes(ckey, fetch_size, page, &database.pool).await {
+    match get_notes(&ckey, fetch_size, page, &database.pool).await {
         Ok((notes, total_count)) => Ok(Json::Ok(json!({
             "data": notes,
             "total_count": total_count
@@ -202,13 +355,13 @@ pub async fn notes(
 
 #[get("/player/rounds?<ckey>&<fetch_size>&<page>")]
 pub async fn rounds(
-    ckey: &str,
+    ckey: Ckey,
     fetch_size: Option<i32>,
     page: Option<i32>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_player_rounds(ckey, fetch_size, page, &database.pool).await {
+    match get_player_rounds(&ckey, fetch_size, page, &database.pool).await {
         Ok((rounds, total_count)) => Ok(Json::Ok(json!({
             "data": rounds,
             "total_count": total_count
@@ -220,12 +373,12 @@ pub async fn rounds(
 
 #[get("/player/friends?<ckey>")]
 pub async fn friends(
-    ckey: &str,
+    ckey: Ckey,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Vec<Friendship>>, Status> {
-    match get_friends(ckey, &database.pool, config).await {
+    match get_friends(&ckey, &database.pool, config).await {
         Ok(friends) => Ok(Json::Ok(friends)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -234,12 +387,12 @@ pub async fn friends(
 
 #[get("/player/friend_invites?<ckey>")]
 pub async fn friend_invites(
-    ckey: &str,
+    ckey: Ckey,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, Status> {
-    match get_friendship_invites(ckey, &database.pool, config).await {
+    match get_friendship_invites(&ckey, &database.pool, config).await {
         Ok((received, sent)) => Ok(Json::Ok(json!({
             "received": received,
             "sent": sent
@@ -251,13 +404,13 @@ pub async fn friend_invites(
 
 #[get("/player/check_friends?<ckey>&<friend>")]
 pub async fn check_friends(
-    ckey: &str,
-    friend: &str,
+    ckey: Ckey,
+    friend: Ckey,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<Friendship>>, Status> {
-    match check_friendship(ckey, friend, &database.pool, config).await {
+    match check_friendship(&ckey, &friend, &database.pool, config).await {
         Ok(friend) => Ok(Json::Ok(friend)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -266,13 +419,13 @@ pub async fn check_friends(
 
 #[post("/player/add_friend?<ckey>&<friend>")]
 pub async fn addfriend(
-    ckey: &str,
-    friend: &str,
+    ckey: Ckey,
+    friend: Ckey,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<Friendship>>, Status> {
-    match add_friend(ckey, friend, &database.pool, config).await {
+    match add_friend(&ckey, &friend, &database.pool, config).await {
         Ok(friend) => Ok(Json::Ok(friend)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -281,13 +434,13 @@ pub async fn addfriend(
 
 #[post("/player/remove_friend?<ckey>&<friendship_id>")]
 pub async fn removefriend(
-    ckey: &str,
+    ckey: Ckey,
     friendship_id: i32,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<Friendship>>, Status> {
-    match remove_friend(ckey, friendship_id, &database.pool, config).await {
+    match remove_friend(&ckey, friendship_id, &database.pool, config).await {
         Ok(friend) => Ok(Json::Ok(friend)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -296,13 +449,13 @@ pub async fn removefriend(
 
 #[post("/player/accept_friend?<ckey>&<friendship_id>")]
 pub async fn acceptfriend(
-    ckey: &str,
+    ckey: Ckey,
     friendship_id: i32,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<Friendship>>, Status> {
-    match accept_friend(ckey, friendship_id, &database.pool, config).await {
+    match accept_friend(&ckey, friendship_id, &database.pool, config).await {
         Ok(friend) => Ok(Json::Ok(friend)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -311,13 +464,13 @@ pub async fn acceptfriend(
 
 #[post("/player/decline_friend?<ckey>&<friendship_id>")]
 pub async fn declinefriend(
-    ckey: &str,
+    ckey: Ckey,
     friendship_id: i32,
     config: &State<Config>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<Friendship>>, Status> {
-    match decline_friend(ckey, friendship_id, &database.pool, config).await {
+    match decline_friend(&ckey, friendship_id, &database.pool, config).await {
         Ok(friend) => Ok(Json::Ok(friend)),
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(_) => Err(Status::InternalServerError),
@@ -326,7 +479,7 @@ pub async fn declinefriend(
 
 #[get("/player/lookup?<ckey>&<ip>&<cid>")]
 pub async fn lookup(
-    ckey: Option<&str>,
+    ckey: Option<Ckey>,
     ip: Option<&str>,
     cid: Option<i64>,
     database: &State<Database>,
@@ -336,8 +489,43 @@ pub async fn lookup(
         return Err(Status::BadRequest);
     }
 
-    match lookup_player(ckey, ip, cid, &database.pool).await {
+    match lookup_player(ckey.as_ref(), ip, cid, &database.pool).await {
         Ok(result) => Ok(Json::Ok(json!(result))),
         Err(_) => Err(Status::InternalServerError),
     }
 }
+
+const DEFAULT_GRAPH_DEPTH: u32 = 2;
+const MAX_GRAPH_DEPTH: u32 = 4;
+const DEFAULT_GRAPH_NODES: usize = 100;
+const MAX_GRAPH_NODES: usize = 500;
+
+#[get("/player/lookup/graph?<ckey>&<ip>&<cid>&<depth>&<max_nodes>")]
+pub async fn lookup_graph(
+    ckey: Option<Ckey>,
+    ip: Option<&str>,
+    cid: Option<&str>,
+    depth: Option<u32>,
+    max_nodes: Option<usize>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Graph>, Status> {
+    let start = match (&ckey, ip, cid) {
+        (Some(ckey), None, None) => LookupStart::Ckey(ckey),
+        (None, Some(ip), None) => LookupStart::Ip(ip),
+        (None, None, Some(cid)) => LookupStart::Cid(cid),
+        _ => return Err(Status::BadRequest),
+    };
+
+    let depth = depth
+        .unwrap_or(DEFAULT_GRAPH_DEPTH)
+        .clamp(1, MAX_GRAPH_DEPTH);
+    let max_nodes = max_nodes
+        .unwrap_or(DEFAULT_GRAPH_NODES)
+        .clamp(1, MAX_GRAPH_NODES);
+
+    match crate::database::lookup_graph(start, depth, max_nodes, &database.pool).await {
+        Ok(graph) => Ok(Json::Ok(graph)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, future::Future};
+
+use rocket::{get, http::Status, State};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use tracing::warn;
+
+use crate::{
+    cache::Cache,
+    ckey::Ckey,
+    config::Config,
+    database::{error::Error, *},
+    http::{byond, discord::User},
+    Database,
+};
+
+use super::{common::ApiKey, patreon::has_patreon_role, player::DEFAULT_ACTIVITY_DAYS, Json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    Roletime,
+    Characters,
+    FavoriteCharacter,
+    Activity,
+    Achievements,
+    Discord,
+    Patreon,
+    ByondMember,
+}
+
+impl Section {
+    const ALL: [Section; 8] = [
+        Section::Roletime,
+        Section::Characters,
+        Section::FavoriteCharacter,
+        Section::Activity,
+        Section::Achievements,
+        Section::Discord,
+        Section::Patreon,
+        Section::ByondMember,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "roletime" => Some(Section::Roletime),
+            "characters" => Some(Section::Characters),
+            "favorite_character" => Some(Section::FavoriteCharacter),
+            "activity" => Some(Section::Activity),
+            "achievements" => Some(Section::Achievements),
+            "discord" => Some(Section::Discord),
+            "patreon" => Some(Section::Patreon),
+            "byond_member" => Some(Section::ByondMember),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Profile {
+    player: Player,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    roletime: Option<Vec<PlayerRoletime>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    characters: Option<Vec<(String, i64)>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    favorite_character: Option<Option<(String, String)>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<Vec<(String, i64)>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    achievements: Option<Vec<Achievement>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discord: Option<Option<User>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patreon: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    byond_member: Option<bool>,
+    /// A fixed code for each requested section that couldn't be loaded: `database_error`,
+    /// `upstream_error` or `internal_error`.
+    errors: BTreeMap<Section, &'static str>,
+}
+
+#[get("/player/profile?<ckey>&<include>")]
+pub async fn index(
+    ckey: Ckey,
+    include: Option<&str>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Profile>, Status> {
+    let sections = match include {
+        Some(include) => include
+            .split(',')
+            .map(|name| Section::from_name(name.trim()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Status::BadRequest)?,
+        None => Section::ALL.to_vec(),
+    };
+
+    match get_profile(&ckey, &sections, config, cache, &database.pool).await {
+        Ok(profile) => Ok(Json::Ok(profile)),
+        Err(Error::PlayerNotFound) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+async fn get_profile(
+    ckey: &Ckey,
+    sections: &[Section],
+    config: &Config,
+    cache: &Cache,
+    pool: &MySqlPool,
+) -> Result<Profile, Error> {
+    let included = |section| sections.contains(&section);
+
+    // Everything stored in the database is read over a single connection, so a profile never
+    // holds more than one regardless of how many sections are requested.
+    let mut connection = pool.acquire().await?;
+
+    let player = match player_by_ckey(ckey, &mut connection).await {
+        Ok(player) => player,
+        Err(e) => {
+            connection.close().await?;
+            return Err(e);
+        }
+    };
+
+    let roletime = fetch(
+        Section::Roletime,
+        sections,
+        roletime_by_ckey(ckey, &mut connection),
+    )
+    .await;
+    let characters = fetch(
+        Section::Characters,
+        sections,
+        characters_by_ckey(ckey, &mut connection),
+    )
+    .await;
+    let favorite_character = fetch(
+        Section::FavoriteCharacter,
+        sections,
+        favorite_character_by_ckey(ckey, &mut connection),
+    )
+    .await;
+    let activity = fetch(
+        Section::Activity,
+        sections,
+        activity_by_ckey(ckey, DEFAULT_ACTIVITY_DAYS, &mut connection),
+    )
+    .await;
+    let achievements = fetch(
+        Section::Achievements,
+        sections,
+        achievements_by_ckey(ckey, None, cache, &mut connection),
+    )
+    .await;
+
+    let discord_id = if included(Section::Discord) || included(Section::Patreon) {
+        match discord_id_by_ckey(ckey, &mut connection).await {
+            Ok(discord_id) => Ok(Some(discord_id)),
+            Err(Error::NotLinked) => Ok(None),
+            Err(e) => Err(failure(Section::Discord, e)),
+        }
+    } else {
+        Ok(None)
+    };
+
+    connection.close().await?;
+
+    let (discord, patreon, byond_member) = tokio::join!(
+        fetch_linked(
+            Section::Discord,
+            sections,
+            discord_id,
+            None,
+            |discord_id| async move {
+                Ok(Some(
+                    fetch_discord_user(discord_id, &config.discord.token, cache).await?,
+                ))
+            }
+        ),
+        fetch_linked(
+            Section::Patreon,
+            sections,
+            discord_id,
+            false,
+            |discord_id| has_patreon_role(discord_id, &config.discord)
+        ),
+        fetch(Section::ByondMember, sections, async {
+            Ok(byond::is_member(ckey).await?)
+        }),
+    );
+
+    let mut errors = BTreeMap::new();
+
+    Ok(Profile {
+        player,
+        roletime: section(Section::Roletime, roletime, &mut errors),
+        characters: section(Section::Characters, characters, &mut errors),
+        favorite_character: section(Section::FavoriteCharacter, favorite_character, &mut errors),
+        activity: section(Section::Activity, activity, &mut errors),
+        achievements: section(Section::Achievements, achievements, &mut errors),
+        discord: section(Section::Discord, discord, &mut errors),
+        patreon: section(Section::Patreon, patreon, &mut errors),
+        byond_member: section(Section::ByondMember, byond_member, &mut errors),
+        errors,
+    })
+}
+
+async fn fetch<T>(
+    section: Section,
+    sections: &[Section],
+    future: impl Future<Output = Result<T, Error>>,
+) -> Option<Result<T, &'static str>> {
+    if !sections.contains(&section) {
+        return None;
+    }
+
+    Some(future.await.map_err(|e| failure(section, e)))
+}
+
+/// Like [`fetch`], for sections that need the player's linked Discord account. Unlinked players
+/// get `unlinked` without a request being made.
+async fn fetch_linked<T, F>(
+    section: Section,
+    sections: &[Section],
+    discord_id: Result<Option<i64>, &'static str>,
+    unlinked: T,
+    load: impl FnOnce(i64) -> F,
+) -> Option<Result<T, &'static str>>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    if !sections.contains(&section) {
+        return None;
+    }
+
+    Some(match discord_id {
+        Ok(Some(discord_id)) => load(discord_id).await.map_err(|e| failure(section, e)),
+        Ok(None) => Ok(unlinked),
+        Err(code) => Err(code),
+    })
+}
+
+/// Logs why a section failed and returns the code reported to the client in its place.
+fn failure(section: Section, e: Error) -> &'static str {
+    warn!("Failed to load {section:?} profile section: {e}");
+
+    match e {
+        Error::Sqlx(_) => "database_error",
+        Error::Reqwest(_) | Error::Http(_) => "upstream_error",
+        _ => "internal_error",
+    }
+}
+
+fn section<T>(
+    section: Section,
+    result: Option<Result<T, &'static str>>,
+    errors: &mut BTreeMap<Section, &'static str>,
+) -> Option<T> {
+    match result? {
+        Ok(value) => Some(value),
+        Err(code) => {
+            errors.insert(section, code);
+            None
+        }
+    }
+}
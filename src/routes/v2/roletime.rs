@@ -0,0 +1,71 @@
+use rocket::{form, get, http::Status, FromForm, State};
+
+use crate::{cache::Cache, config::Config, database::*, date::OptionalDate, Database};
+
+use super::{common::ApiKey, Json};
+
+const DEFAULT_LIMIT: u32 = 15;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(FromForm)]
+pub struct TopQuery<'r> {
+    job: Option<&'r str>,
+    department: Option<&'r str>,
+    limit: Option<u32>,
+    since: OptionalDate,
+}
+
+#[get("/roletime/top?<query..>")]
+pub async fn top(
+    query: form::Result<'_, TopQuery<'_>>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<JobRoletime>>, Status> {
+    let Ok(query) = query else {
+        return Err(Status::BadRequest);
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let (key, jobs) = match (query.job, query.department) {
+        (Some(job), None) => (format!("job:{}", job.to_lowercase()), vec![job]),
+        (None, Some(department)) => {
+            let Some(department) = config.jobs.department(department) else {
+                return Err(Status::NotFound);
+            };
+
+            (
+                format!("department:{department}"),
+                config.jobs.department_jobs(department),
+            )
+        }
+        _ => return Err(Status::BadRequest),
+    };
+
+    let since = query
+        .since
+        .map(|since| since.to_string())
+        .unwrap_or_default();
+    let key = format!("{key}:{limit}:{since}");
+
+    let roletimes = cache
+        .top_roletime
+        .get_or_try_load(key, || async {
+            match (*query.since, query.job) {
+                (Some(since), _) => {
+                    get_top_roletime_since(&jobs, since, limit, &database.pool).await
+                }
+                (None, Some(job)) => get_top_roletime(job, limit, &database.pool).await,
+                (None, None) => get_top_department_roletime(&jobs, limit, &database.pool).await,
+            }
+        })
+        .await;
+
+    let Ok(roletimes) = roletimes else {
+        return Err(Status::InternalServerError);
+    };
+
+    Ok(Json::Ok(roletimes))
+}
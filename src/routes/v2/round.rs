@@ -1,43 +1,133 @@
-use rocket::{get, http::Status as HttpStatus, State};
+use rocket::{form, get, http::Status as HttpStatus, State};
 
 use serde_json::{json, Value};
 
 use crate::{
+    cache::Cache,
     config::Config,
     database::{error::Error, *},
     Database,
 };
 
-use super::{common::ApiKey, Json};
+use super::{
+    common::{ApiKey, KeyScope},
+    Json,
+};
 
 #[get("/round?<round_id>")]
 pub async fn index(
     round_id: i32,
     database: &State<Database>,
     config: &State<Config>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<RoundData>, HttpStatus> {
-    match get_round(round_id, config, &database.pool).await {
+    match get_round(round_id, config, cache, &database.pool).await {
         Ok(round) => Ok(Json::Ok(round)),
         Err(Error::RoundNotFound) => Err(HttpStatus::NotFound),
         Err(_) => Err(HttpStatus::InternalServerError),
     }
 }
 
-#[get("/rounds?<fetch_size>&<page>&<round_id>")]
+#[get("/rounds?<fetch_size>&<page>&<filters..>")]
 pub async fn rounds(
     fetch_size: Option<i32>,
     page: Option<i32>,
-    round_id: Option<i32>,
+    filters: form::Result<'_, RoundFilters<'_>>,
     config: &State<Config>,
+    cache: &State<Cache>,
     database: &State<Database>,
     _api_key: ApiKey,
 ) -> Result<Json<Value>, HttpStatus> {
-    match get_rounds(fetch_size, page, round_id, config, &database.pool).await {
+    let Ok(filters) = filters else {
+        return Err(HttpStatus::BadRequest);
+    };
+
+    match get_rounds(fetch_size, page, &filters, config, cache, &database.pool).await {
         Ok((rounds, total_count)) => Ok(Json::Ok(json!({
             "data": rounds,
             "total_count": total_count
         }))),
+        Err(Error::ServerNotFound) => Err(HttpStatus::NotFound),
+        Err(_) => Err(HttpStatus::InternalServerError),
+    }
+}
+
+#[get("/round/feedback?<round_id>&<key>")]
+pub async fn feedback(
+    round_id: u32,
+    key: Option<&str>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Value>, HttpStatus> {
+    let feedback = match key {
+        Some(key) => get_round_feedback(round_id, key, config, cache, &database.pool)
+            .await
+            .map(|feedback| json!(feedback)),
+        None => get_feedback_keys(round_id, config, cache, &database.pool)
+            .await
+            .map(|keys| json!(keys)),
+    };
+
+    match feedback {
+        Ok(feedback) => Ok(Json::Ok(feedback)),
+        Err(Error::RoundNotFound | Error::FeedbackNotFound) => Err(HttpStatus::NotFound),
+        Err(_) => Err(HttpStatus::InternalServerError),
+    }
+}
+
+#[get("/feedback/series?<series..>")]
+pub async fn feedback_series(
+    series: form::Result<'_, FeedbackSeries<'_>>,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<FeedbackPoint>>, HttpStatus> {
+    let Ok(series) = series else {
+        return Err(HttpStatus::BadRequest);
+    };
+
+    match get_feedback_series(&series, config, cache, &database.pool).await {
+        Ok(points) => Ok(Json::Ok(points)),
+        Err(Error::FeedbackNotFound) => Err(HttpStatus::NotFound),
+        Err(Error::InvalidJsonPath) => Err(HttpStatus::BadRequest),
+        Err(_) => Err(HttpStatus::InternalServerError),
+    }
+}
+
+#[get("/round/manifest?<round_id>")]
+pub async fn manifest(
+    round_id: i32,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    api_key: ApiKey,
+) -> Result<Json<Vec<ManifestEntry>>, HttpStatus> {
+    let show_hidden = api_key.scope == KeyScope::Full;
+
+    match get_round_manifest(round_id, show_hidden, config, cache, &database.pool).await {
+        Ok(manifest) => Ok(Json::Ok(manifest)),
+        Err(Error::RoundNotFound) => Err(HttpStatus::NotFound),
+        Err(_) => Err(HttpStatus::InternalServerError),
+    }
+}
+
+#[get("/round/timeline?<round_id>")]
+pub async fn timeline(
+    round_id: i32,
+    database: &State<Database>,
+    config: &State<Config>,
+    cache: &State<Cache>,
+    api_key: ApiKey,
+) -> Result<Json<Vec<TimelineItem>>, HttpStatus> {
+    let show_hidden = api_key.scope == KeyScope::Full;
+
+    match get_round_timeline(round_id, show_hidden, config, cache, &database.pool).await {
+        Ok(timeline) => Ok(Json::Ok(timeline)),
+        Err(Error::RoundNotFound) => Err(HttpStatus::NotFound),
         Err(_) => Err(HttpStatus::InternalServerError),
     }
 }
@@ -2,14 +2,19 @@ use rocket::{get, State};
 
 use crate::{
     byond::{get_server_status, Status},
+    cache::Cache,
     config::Config,
 };
 
-use super::Json;
+use super::{common::RateLimit, Json};
 
 #[get("/server")]
-pub async fn index(config: &State<Config>) -> Json<Vec<Status>> {
-    let status = get_server_status(config).await;
+pub async fn index(
+    config: &State<Config>,
+    cache: &State<Cache>,
+    _rate_limit: RateLimit,
+) -> Json<Vec<Status>> {
+    let status = get_server_status(config, cache).await;
 
     Json::Ok(status)
 }
@@ -0,0 +1,78 @@
+use chrono::{Duration, Utc};
+use rocket::{form, get, http::Status, State};
+
+use crate::{
+    config::Config,
+    database::*,
+    date::{Date, OptionalDate},
+    Database,
+};
+
+use super::{common::ApiKey, Json};
+
+/// Weeks covered before `to`, or today, when no `from` is given.
+const DEFAULT_WEEKS: i64 = 12;
+
+/// Validates a `[from, to)` range, bounding it to the last [`DEFAULT_WEEKS`] when open-ended.
+fn window(
+    from: form::Result<'_, OptionalDate>,
+    to: form::Result<'_, OptionalDate>,
+) -> Result<(Option<Date>, Option<Date>), Status> {
+    let (Ok(OptionalDate(from)), Ok(OptionalDate(to))) = (from, to) else {
+        return Err(Status::BadRequest);
+    };
+
+    let from = from.unwrap_or_else(|| {
+        let end = to.map_or_else(|| Utc::now().date_naive(), |to| *to);
+        Date::from(end - Duration::weeks(DEFAULT_WEEKS))
+    });
+
+    Ok((Some(from), to))
+}
+
+#[get("/stats/moderation?<from>&<to>")]
+pub async fn moderation(
+    from: form::Result<'_, OptionalDate>,
+    to: form::Result<'_, OptionalDate>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<ModerationWeek>>, Status> {
+    let (from, to) = window(from, to)?;
+
+    match get_moderation_stats(from, to, &database.pool).await {
+        Ok(weeks) => Ok(Json::Ok(weeks)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+#[get("/stats/maps?<from>&<to>")]
+pub async fn maps(
+    from: form::Result<'_, OptionalDate>,
+    to: form::Result<'_, OptionalDate>,
+    database: &State<Database>,
+    config: &State<Config>,
+    _api_key: ApiKey,
+) -> Result<Json<Vec<MapSummary>>, Status> {
+    let (from, to) = window(from, to)?;
+
+    match get_map_stats(from, to, config, &database.pool).await {
+        Ok(maps) => Ok(Json::Ok(maps)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// Without `from`, covers the [`DEFAULT_WEEKS`] before `to`, or today, like the other stats.
+#[get("/stats/antagonists?<from>&<to>")]
+pub async fn antagonists(
+    from: form::Result<'_, OptionalDate>,
+    to: form::Result<'_, OptionalDate>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<AntagonistStats>, Status> {
+    let (from, to) = window(from, to)?;
+
+    match get_antagonist_stats(from, to, &database.pool).await {
+        Ok(stats) => Ok(Json::Ok(stats)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
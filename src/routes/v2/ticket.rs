@@ -0,0 +1,27 @@
+use rocket::{form, get, http::Status, State};
+use serde_json::{json, Value};
+
+use crate::{database::*, Database};
+
+use super::{common::ApiKey, Json};
+
+#[get("/tickets?<fetch_size>&<page>&<filters..>")]
+pub async fn index(
+    fetch_size: Option<i32>,
+    page: Option<i32>,
+    filters: form::Result<'_, TicketFilters<'_>>,
+    database: &State<Database>,
+    _api_key: ApiKey,
+) -> Result<Json<Value>, Status> {
+    let Ok(filters) = filters else {
+        return Err(Status::BadRequest);
+    };
+
+    match search_tickets(&filters, fetch_size, page, &database.pool).await {
+        Ok((tickets, total_count)) => Ok(Json::Ok(json!({
+            "data": tickets,
+            "total_count": total_count
+        }))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -2,6 +2,8 @@ use rocket::{http::Status, post, serde::json, State};
 use serde::Deserialize;
 
 use crate::{
+    cache::Cache,
+    ckey::Ckey,
     database::{error::Error, *},
     Database,
 };
@@ -12,7 +14,7 @@ use super::{common::ApiKey, Json};
 pub struct VerifyData<'r> {
     discord_id: &'r str,
     one_time_token: Option<&'r str>,
-    ckey: Option<&'r str>,
+    ckey: Option<Ckey>,
     skip_ckey: Option<bool>,
 }
 
@@ -20,6 +22,7 @@ pub struct VerifyData<'r> {
 pub async fn index(
     data: json::Json<VerifyData<'_>>,
     database: &State<Database>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<Option<String>>, Status> {
     if data.one_time_token.is_some() ^ data.ckey.is_none() {
@@ -29,13 +32,17 @@ pub async fn index(
     match verify_discord(
         data.discord_id,
         data.one_time_token,
-        data.ckey,
+        data.ckey.as_ref(),
         data.skip_ckey,
         &database.pool,
     )
     .await
     {
-        Ok(ckey) => Ok(Json::Ok(ckey)),
+        Ok(ckey) => {
+            cache.patrons.invalidate(&());
+            cache.invalidate_shared("patrons").await;
+            Ok(Json::Ok(ckey))
+        }
         Err(Error::DiscordInUse(ckey)) => Ok(Json::Conflict(Some(ckey))),
         Err(Error::CkeyInUse(discord_id)) => Ok(Json::Conflict(Some(format!("@{discord_id}")))),
         Err(Error::TokenInvalid) => Err(Status::NotFound),
@@ -47,21 +54,26 @@ pub async fn index(
 #[derive(Deserialize)]
 pub struct UnverifyData<'r> {
     discord_id: Option<&'r str>,
-    ckey: Option<&'r str>,
+    ckey: Option<Ckey>,
 }
 
 #[post("/unverify", data = "<data>")]
 pub async fn unverify(
     data: json::Json<UnverifyData<'_>>,
     database: &State<Database>,
+    cache: &State<Cache>,
     _api_key: ApiKey,
 ) -> Result<Json<String>, Status> {
     if data.discord_id.is_some() ^ data.ckey.is_none() {
         return Err(Status::BadRequest);
     }
 
-    match unverify_discord(data.discord_id, data.ckey, &database.pool).await {
-        Ok(account) => Ok(Json::Ok(account)),
+    match unverify_discord(data.discord_id, data.ckey.as_ref(), &database.pool).await {
+        Ok(account) => {
+            cache.patrons.invalidate(&());
+            cache.invalidate_shared("patrons").await;
+            Ok(Json::Ok(account))
+        }
         Err(Error::PlayerNotFound) => Err(Status::NotFound),
         Err(Error::NotLinked) => Err(Status::Conflict),
         Err(_) => Err(Status::InternalServerError),
@@ -0,0 +1,51 @@
+use rocket::http::Status;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn rarity() {
+    let harness = Harness::new().await;
+
+    let achievements = harness.get_json("/v2/achievements").await;
+    let achievements = achievements.as_array().unwrap();
+    assert_eq!(achievements.len(), 3);
+    assert_eq!(achievements[0]["achievement_key"], "Clean Shift");
+    assert_eq!(achievements[0]["holders"], 2);
+    assert_eq!(achievements[0]["rarity"], 50.0);
+    assert_eq!(achievements[2]["achievement_key"], "Meteors");
+    assert_eq!(achievements[2]["holders"], 0);
+    assert_eq!(achievements[2]["rarity"], 0.0);
+
+    let achievements = harness.get_json("/v2/achievements?active_days=3650").await;
+    assert_eq!(achievements[0]["rarity"], 50.0);
+    assert_eq!(achievements[1]["achievement_key"], "Tendril Score");
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn leaderboard_and_recent() {
+    let harness = Harness::new().await;
+
+    let scores = harness
+        .get_json("/v2/achievements/top?achievement_key=Tendril%20Score")
+        .await;
+    assert_eq!(scores[0]["ckey"], "someplayer");
+    assert_eq!(scores[0]["value"], 7);
+    assert_eq!(scores[1]["ckey"], "otherplayer");
+
+    let response = harness
+        .get("/v2/achievements/top?achievement_key=Clean%20Shift")
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let recent = harness.get_json("/v2/achievements/recent?limit=2").await;
+    let recent = recent.as_array().unwrap();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0]["timestamp"], "2024-01-03 12:00:00");
+
+    let recent = harness
+        .get_json("/v2/achievements/recent?achievement_type=score")
+        .await;
+    assert_eq!(recent.as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,104 @@
+use rocket::http::Status;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn ban() {
+    let harness = Harness::new().await;
+
+    let ban = harness.get_json("/v2/ban?id=3").await;
+    assert_eq!(ban["ckey"], "otherplayer");
+    assert_eq!(ban["edit_history"][0]["editor"], "AdminGuy");
+    assert_eq!(ban["edit_history"][0]["field"], "duration");
+    assert_eq!(ban["edit_history"][0]["old"], "1440 MINUTES");
+    assert_eq!(ban["edit_history"][0]["new"], "3 DAY");
+
+    let response = harness.get("/v2/ban?id=404").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn search() {
+    let harness = Harness::new().await;
+
+    let bans = harness.get_json("/v2/bans").await;
+    assert_eq!(bans["total_count"], 3);
+    assert_eq!(bans["data"][0]["ckey"], "altplayer");
+    assert_eq!(bans["data"][1]["ckey"], "otherplayer");
+    assert_eq!(bans["data"][2]["id"], 1);
+    assert_eq!(bans["data"][2]["roles"], "Security Officer, Warden");
+
+    let bans = harness.get_json("/v2/bans?status=active").await;
+    assert_eq!(bans["total_count"], 2);
+    assert_eq!(bans["data"][1]["ckey"], "someplayer");
+
+    let bans = harness.get_json("/v2/bans?status=unbanned").await;
+    assert_eq!(bans["data"][0]["ckey"], "otherplayer");
+
+    let bans = harness.get_json("/v2/bans?role=Warden").await;
+    assert_eq!(bans["total_count"], 1);
+    assert_eq!(bans["data"][0]["roles"], "Security Officer, Warden");
+
+    let bans = harness
+        .get_json("/v2/bans?reason=grief&a_ckey=adminguy")
+        .await;
+    assert_eq!(bans["total_count"], 1);
+    assert_eq!(bans["data"][0]["ckey"], "otherplayer");
+
+    let bans = harness
+        .get_json("/v2/bans?from=2024-01-01&to=2024-01-02&fetch_size=1")
+        .await;
+    assert_eq!(bans["total_count"], 1);
+    assert_eq!(bans["data"].as_array().unwrap().len(), 1);
+
+    let bans = harness.get_json("/v2/bans?server=Test%20Station").await;
+    assert_eq!(bans["total_count"], 2);
+    assert_eq!(bans["data"][0]["round_id"], 2);
+    assert_eq!(bans["data"][1]["round_id"], 1);
+
+    let bans = harness.get_json("/v2/bans?reason=%25").await;
+    assert_eq!(bans["total_count"], 0);
+
+    let response = harness.get("/v2/bans?server=Nowhere").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = harness.get("/v2/bans?from=yesterday").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn check() {
+    let harness = Harness::new().await;
+
+    let check = harness.get_json("/v2/ban/check?ckey=someplayer").await;
+    assert_eq!(check["banned"], false);
+
+    let check = harness
+        .get_json("/v2/ban/check?ckey=someplayer&role=Warden")
+        .await;
+    assert_eq!(check["banned"], true);
+    assert_eq!(check["bans"][0]["roles"], "Security Officer, Warden");
+    assert_eq!(check["bans"][0]["matched"], serde_json::json!(["ckey"]));
+
+    let check = harness
+        .get_json("/v2/ban/check?ckey=someplayer&ip=10.0.0.1&cid=1111111111")
+        .await;
+    assert_eq!(check["banned"], true);
+    assert_eq!(check["bans"][0]["ckey"], "altplayer");
+    assert_eq!(check["bans"][0]["matched"], serde_json::json!(["ip"]));
+
+    let check = harness
+        .get_json("/v2/ban/check?ip=10.0.0.1&role=Warden")
+        .await;
+    assert_eq!(check["bans"].as_array().unwrap().len(), 1);
+    assert_eq!(check["bans"][0]["ckey"], "altplayer");
+
+    let check = harness.get_json("/v2/ban/check?ckey=otherplayer").await;
+    assert_eq!(check["banned"], false);
+
+    let response = harness.get("/v2/ban/check").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
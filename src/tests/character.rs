@@ -0,0 +1,70 @@
+use rocket::http::Status;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn character() {
+    let harness = Harness::new().await;
+
+    let character = harness.get_json("/v2/character?name=Jane%20Roe").await;
+    assert_eq!(character["name"], "Jane Roe");
+    assert_eq!(character["appearances"].as_array().unwrap().len(), 3);
+    assert_eq!(character["appearances"][0]["round_id"], 3);
+    assert_eq!(character["appearances"][1]["ckey"], "altplayer");
+    assert_eq!(character["appearances"][1]["latejoin"], true);
+    assert_eq!(character["deaths"].as_array().unwrap().len(), 2);
+    assert_eq!(character["citations_sent"][0]["crime"], "Littering");
+    assert_eq!(character["citations_received"][0]["crime"], "Trespassing");
+
+    let character = harness
+        .get_json("/v2/character?name=Jane%20Roe&ckey=otherplayer")
+        .await;
+    assert_eq!(character["appearances"].as_array().unwrap().len(), 2);
+    assert_eq!(character["deaths"].as_array().unwrap().len(), 1);
+    assert_eq!(character["deaths"][0]["round_id"], 1);
+
+    let response = harness.get("/v2/character?name=Nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    harness
+        .post("/v2/autocomplete/ckey/hide?ckey=altplayer&hid_by=1")
+        .await;
+
+    let character = harness.get_json("/v2/character?name=Jane%20Roe").await;
+    assert!(character["appearances"][1]["ckey"].is_null());
+
+    let response = harness
+        .get("/v2/character?name=Jane%20Roe&ckey=altplayer")
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn character_skips_unfinished_rounds() {
+    let harness = Harness::new().await;
+
+    harness
+        .execute(
+            "INSERT INTO `round` (`id`, `initialize_datetime`, `start_datetime`, `server_ip`, `server_port`, `commit_hash`, `game_mode`, `map_name`, `station_name`) VALUES
+              (4, '2024-01-04 10:00:00', '2024-01-04 10:05:00', INET_ATON('203.0.113.7'), 1337, 'dddddddddddddddddddddddddddddddddddddddd', 'dynamic', 'MetaStation', 'Space Station 13');
+            INSERT INTO `manifest` (`server_ip`, `server_port`, `round_id`, `ckey`, `character_name`, `job`, `special`, `latejoin`, `timestamp`) VALUES
+              (INET_ATON('203.0.113.7'), 1337, 4, 'otherplayer', 'Jane Roe', 'Medical Doctor', 'Heretic', 0, '2024-01-04 10:05:00'),
+              (INET_ATON('203.0.113.7'), 1337, 4, 'someplayer', 'Live Only', 'Assistant', 'Traitor', 0, '2024-01-04 10:05:00');
+            INSERT INTO `death` (`pod`, `x_coord`, `y_coord`, `z_coord`, `mapname`, `server_ip`, `server_port`, `round_id`, `tod`, `job`, `special`, `name`, `byondkey`, `laname`, `lakey`, `bruteloss`, `brainloss`, `fireloss`, `oxyloss`, `toxloss`, `cloneloss`, `staminaloss`, `last_words`, `suicide`) VALUES
+              ('Medbay', 100, 120, 2, 'MetaStation', INET_ATON('203.0.113.7'), 1337, 4, '2024-01-04 10:30:00', 'Medical Doctor', 'Heretic', 'Jane Roe', 'otherplayer', 'John Doe', 'someplayer', 150, 0, 0, 0, 0, 0, 0, NULL, 0);
+            INSERT INTO `citation` (`server_ip`, `server_port`, `round_id`, `sender`, `sender_ic`, `recipient`, `crime`, `crime_desc`, `fine`, `paid`, `timestamp`) VALUES
+              (INET_ATON('203.0.113.7'), 1337, 4, 'someplayer', 'John Doe', 'Jane Roe', 'Arson', NULL, 100, 0, '2024-01-04 10:20:00');",
+        )
+        .await;
+
+    let character = harness.get_json("/v2/character?name=Jane%20Roe").await;
+    assert_eq!(character["appearances"].as_array().unwrap().len(), 3);
+    assert_eq!(character["appearances"][0]["round_id"], 3);
+    assert_eq!(character["deaths"].as_array().unwrap().len(), 2);
+    assert_eq!(character["citations_received"].as_array().unwrap().len(), 1);
+
+    let response = harness.get("/v2/character?name=Live%20Only").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
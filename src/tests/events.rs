@@ -0,0 +1,102 @@
+use rocket::http::{Header, Status};
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn overview() {
+    let harness = Harness::new().await;
+
+    let overview = harness.get_json("/v2/events/overview?limit=10").await;
+    let round = overview
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|round| round["round_id"] == 1)
+        .unwrap();
+    assert_eq!(round["deaths"], 2);
+    assert_eq!(round["citations"], 1);
+    assert_eq!(round["crimes"], 1);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn deaths_citations_and_crimes() {
+    let harness = Harness::new().await;
+
+    let deaths = harness.get_json("/v2/events/deaths").await;
+    assert_eq!(deaths["total_count"], 4);
+
+    let citations = harness.get_json("/v2/events/citations").await;
+    assert_eq!(citations["total_count"], 2);
+
+    let crimes = harness.get_json("/v2/events/crimes").await;
+    assert_eq!(crimes["total_count"], 1);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn killers() {
+    let harness = Harness::new().await;
+
+    let killers = harness.get_json("/v2/events/killers").await;
+    assert_eq!(
+        killers,
+        serde_json::json!([
+            { "ckey": "someplayer", "kills": 2 },
+            { "ckey": "otherplayer", "kills": 1 }
+        ])
+    );
+
+    let killers = harness
+        .get_json("/v2/events/killers?since=2024-01-02")
+        .await;
+    assert_eq!(
+        killers,
+        serde_json::json!([{ "ckey": "otherplayer", "kills": 1 }])
+    );
+
+    let response = harness.get("/v2/events/killers?since=2024-01-02T00").await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness
+        .client
+        .get("/v2/events/killers")
+        .header(Header::new("X-EXP-KEY", "test-exposed-secret"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn deaths_skip_unfinished_rounds() {
+    let harness = Harness::new().await;
+
+    harness
+        .execute(
+            "INSERT INTO `round` (`id`, `initialize_datetime`, `start_datetime`, `server_ip`, `server_port`, `commit_hash`, `game_mode`, `map_name`, `station_name`) VALUES
+              (4, '2024-01-04 10:00:00', '2024-01-04 10:05:00', INET_ATON('203.0.113.7'), 1337, 'dddddddddddddddddddddddddddddddddddddddd', 'dynamic', 'MetaStation', 'Space Station 13');
+            INSERT INTO `death` (`pod`, `x_coord`, `y_coord`, `z_coord`, `mapname`, `server_ip`, `server_port`, `round_id`, `tod`, `job`, `special`, `name`, `byondkey`, `laname`, `lakey`, `bruteloss`, `brainloss`, `fireloss`, `oxyloss`, `toxloss`, `cloneloss`, `staminaloss`, `last_words`, `suicide`) VALUES
+              ('Medbay', 100, 120, 2, 'MetaStation', INET_ATON('203.0.113.7'), 1337, 4, '2024-01-04 10:30:00', 'Medical Doctor', 'Heretic', 'Jane Roe', 'otherplayer', 'Hidden Killer', 'altplayer', 150, 0, 0, 0, 0, 0, 0, NULL, 0);",
+        )
+        .await;
+
+    let deaths = harness.get_json("/v2/player/deaths?ckey=otherplayer").await;
+    assert_eq!(deaths["total_count"], 1);
+    assert_eq!(deaths["data"][0]["round_id"], 1);
+
+    let stats = harness
+        .get_json("/v2/player/deaths/stats?ckey=otherplayer")
+        .await;
+    assert_eq!(stats["deaths"], 1);
+
+    let killers = harness.get_json("/v2/events/killers").await;
+    assert_eq!(
+        killers,
+        serde_json::json!([
+            { "ckey": "someplayer", "kills": 2 },
+            { "ckey": "otherplayer", "kills": 1 }
+        ])
+    );
+}
@@ -0,0 +1,265 @@
+//! Runs the API against a throwaway MySQL/MariaDB database.
+//!
+//! Every [`Harness`] creates its own game and API databases, loads
+//! `tests/fixtures/game_schema.sql`, `tests/fixtures/game_data.sql` and `database_schema.sql`
+//! into them and mounts the real routes on a local Rocket client. The server is configured with:
+//!
+//! - `TEST_DATABASE_HOST` (default `127.0.0.1`)
+//! - `TEST_DATABASE_PORT` (default `3306`)
+//! - `TEST_DATABASE_USER` (default `root`)
+//! - `TEST_DATABASE_PASSWORD` (default empty)
+//!
+//! Some queries group by a subset of their selected columns, so MariaDB or a MySQL server without
+//! `ONLY_FULL_GROUP_BY` is required. The tests are ignored by default, run them with
+//! `cargo test -- --ignored`. The databases are dropped when the [`Harness`] is, even if the test
+//! panicked.
+
+use std::{
+    env,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rocket::{
+    http::{Header, Status},
+    local::asynchronous::{Client, LocalResponse},
+};
+use serde_json::Value;
+use sqlx::{
+    mysql::{MySqlConnection, MySqlPoolOptions},
+    Connection as _, Executor as _,
+};
+use urlencoding::encode;
+
+use crate::{cache::Cache, config::Config, database::Database};
+
+const GAME_SCHEMA: &str = include_str!("../../tests/fixtures/game_schema.sql");
+const GAME_DATA: &str = include_str!("../../tests/fixtures/game_data.sql");
+const API_SCHEMA: &str = include_str!("../../database_schema.sql");
+
+pub const API_KEY: &str = "test-secret";
+
+static DATABASE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Harness {
+    pub client: Client,
+    admin_url: String,
+    game_database: String,
+    api_database: String,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        let host = env::var("TEST_DATABASE_HOST").unwrap_or("127.0.0.1".to_string());
+        let port = env::var("TEST_DATABASE_PORT").unwrap_or("3306".to_string());
+        let user = env::var("TEST_DATABASE_USER").unwrap_or("root".to_string());
+        let password = env::var("TEST_DATABASE_PASSWORD").unwrap_or_default();
+
+        let prefix = format!(
+            "psychonaut_test_{}_{}",
+            std::process::id(),
+            DATABASE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let game_database = format!("{prefix}_game");
+        let api_database = format!("{prefix}_api");
+
+        let admin_url = format!("mysql://{user}:{}@{host}:{port}", encode(&password));
+
+        let admin = MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect(&admin_url)
+            .await
+            .expect("failed to connect to the test database server");
+
+        for (database, scripts) in [
+            (&game_database, [GAME_SCHEMA, GAME_DATA].as_slice()),
+            (&api_database, [API_SCHEMA].as_slice()),
+        ] {
+            admin
+                .execute(format!("CREATE DATABASE `{database}`").as_str())
+                .await
+                .unwrap();
+
+            let mut connection = admin.acquire().await.unwrap();
+            connection
+                .execute(format!("USE `{database}`").as_str())
+                .await
+                .unwrap();
+
+            for script in scripts {
+                for statement in statements(script) {
+                    connection.execute(statement.as_str()).await.unwrap();
+                }
+            }
+        }
+
+        let config: Config = toml::from_str(&format!(
+            r#"
+            address = "127.0.0.1"
+            port = 0
+            secret = "{API_KEY}"
+            dev_secret = "test-dev-secret"
+            dev_routes = []
+            exposed_secret = "test-exposed-secret"
+            exposed_routes = ["/v2/round/manifest", "/v2/round/timeline", "/v2/events/killers"]
+            rate_limit = 1000
+            cli_colors = false
+            log_level = "off"
+            killers_leaderboard = true
+
+            [discord]
+            token = ""
+            guild = 0
+            patreon_role = 0
+
+            [database]
+            user = "{user}"
+            password = "{password}"
+            host = "{host}"
+            port = {port}
+            game_database = "{game_database}"
+            api_database = "{api_database}"
+
+            [cache]
+            backend = "memory"
+
+            [feedback]
+            denylist = ["roundend_nukedisk", "admin_*"]
+
+            [jobs.departments]
+            Command = ["Captain", "Head of Security"]
+            Security = ["Head of Security", "Warden", "Security Officer"]
+            Medical = ["Medical Doctor"]
+
+            [jobs.aliases]
+            "Security Cadet" = "Security Officer"
+
+            [[servers]]
+            name = "Test Station"
+            address = "127.0.0.1:1337"
+            connection_address = "203.0.113.7:1337"
+            error_message = "Offline"
+            "#
+        ))
+        .unwrap();
+
+        let database = Database::new(&config.database).unwrap();
+        let cache = Cache::new(&config.cache).await.unwrap();
+        let rocket = crate::build(config, database, cache).unwrap();
+        let client = Client::tracked(rocket).await.unwrap();
+
+        admin.close().await;
+
+        Self {
+            client,
+            admin_url,
+            game_database,
+            api_database,
+        }
+    }
+
+    pub async fn get(&self, uri: &str) -> LocalResponse<'_> {
+        self.client
+            .get(uri.to_string())
+            .header(Header::new("X-API-KEY", API_KEY))
+            .dispatch()
+            .await
+    }
+
+    pub async fn post(&self, uri: &str) -> LocalResponse<'_> {
+        self.client
+            .post(uri.to_string())
+            .header(Header::new("X-API-KEY", API_KEY))
+            .dispatch()
+            .await
+    }
+
+    pub async fn post_json(&self, uri: &str, body: Value) -> LocalResponse<'_> {
+        self.client
+            .post(uri.to_string())
+            .header(Header::new("X-API-KEY", API_KEY))
+            .json(&body)
+            .dispatch()
+            .await
+    }
+
+    /// Runs `sql` against the game database, for rows only one test needs.
+    pub async fn execute(&self, sql: &str) {
+        let mut connection =
+            MySqlConnection::connect(&format!("{}/{}", self.admin_url, self.game_database))
+                .await
+                .unwrap();
+
+        for statement in statements(sql) {
+            connection.execute(statement.as_str()).await.unwrap();
+        }
+
+        connection.close().await.unwrap();
+    }
+
+    /// Requests `uri` and returns its JSON body, failing unless the response is `200 OK`.
+    pub async fn get_json(&self, uri: &str) -> Value {
+        let response = self.get(uri).await;
+        assert_eq!(response.status(), Status::Ok, "GET {uri}");
+        response.into_json().await.unwrap()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let url = self.admin_url.clone();
+        let databases = [self.game_database.clone(), self.api_database.clone()];
+
+        // the test's runtime may be unwinding from a failed assert, so use a fresh one
+        let result = std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(async {
+                    let mut connection = MySqlConnection::connect(&url).await?;
+
+                    for database in databases {
+                        connection
+                            .execute(format!("DROP DATABASE IF EXISTS `{database}`").as_str())
+                            .await?;
+                    }
+
+                    connection.close().await?;
+
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+                })
+        })
+        .join();
+
+        if !matches!(result, Ok(Ok(()))) {
+            eprintln!(
+                "failed to drop {} and {}",
+                self.game_database, self.api_database
+            );
+        }
+    }
+}
+
+/// Splits a SQL script into statements on semicolons that end a line.
+fn statements(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+
+    for line in script.lines() {
+        if line.trim_start().starts_with("--") {
+            continue;
+        }
+
+        current.push_str(line);
+        current.push('\n');
+
+        if line.trim_end().ends_with(';') {
+            statements.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current);
+    }
+
+    statements
+}
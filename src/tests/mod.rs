@@ -0,0 +1,11 @@
+mod harness;
+
+mod achievement;
+mod ban;
+mod character;
+mod events;
+mod player;
+mod round;
+mod stats;
+mod ticket;
+mod verify;
@@ -0,0 +1,509 @@
+use rocket::http::Status;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn player() {
+    let harness = Harness::new().await;
+
+    let player = harness.get_json("/v2/player?ckey=Some%20Player").await;
+    assert_eq!(player["ckey"], "someplayer");
+    assert_eq!(player["byond_key"], "Some Player");
+
+    let response = harness.get("/v2/player?ckey=nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn requires_api_key() {
+    let harness = Harness::new().await;
+
+    let response = harness
+        .client
+        .get("/v2/player?ckey=someplayer")
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn ban() {
+    let harness = Harness::new().await;
+
+    let bans = harness.get_json("/v2/player/ban?ckey=someplayer").await;
+    let bans = bans.as_array().unwrap();
+    assert_eq!(bans.len(), 1);
+    let roles = bans[0]["roles"].as_str().unwrap();
+    assert!(roles.contains("Security Officer") && roles.contains("Warden"));
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn characters_and_roletime() {
+    let harness = Harness::new().await;
+
+    let characters = harness
+        .get_json("/v2/player/characters?ckey=someplayer")
+        .await;
+    assert_eq!(characters.as_array().unwrap().len(), 2);
+
+    let roletime = harness
+        .get_json("/v2/player/roletime?ckey=someplayer")
+        .await;
+    assert_eq!(roletime.as_array().unwrap().len(), 3);
+
+    let top = harness
+        .get_json("/v2/player/roletime/top?job=security%20officer")
+        .await;
+    let top = top.as_array().unwrap();
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[0]["ckey"], "someplayer");
+
+    let favorite = harness
+        .get_json("/v2/player/favorite_character?ckey=someplayer")
+        .await;
+    assert!(favorite.to_string().contains("John Doe"));
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn achievements() {
+    let harness = Harness::new().await;
+
+    let achievements = harness
+        .get_json("/v2/player/achievements?ckey=someplayer")
+        .await;
+    assert_eq!(achievements.as_array().unwrap().len(), 2);
+    // someplayer and adminguy are the only recently seen players.
+    assert_eq!(achievements[0]["rarity"], 50.0);
+
+    let scores = harness
+        .get_json("/v2/player/achievements?ckey=someplayer&achievement_type=score")
+        .await;
+    let scores = scores.as_array().unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0]["value"], 7);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn tickets_and_messages() {
+    let harness = Harness::new().await;
+
+    let tickets = harness
+        .get_json("/v2/player/tickets?ckey=otherplayer")
+        .await;
+    assert_eq!(tickets["total_count"], 1);
+    assert_eq!(tickets["data"][0]["logs"].as_array().unwrap().len(), 4);
+
+    let messages = harness
+        .get_json("/v2/player/messages?ckey=someplayer")
+        .await;
+    assert_eq!(messages["total_count"], 1);
+
+    let notes = harness.get_json("/v2/player/notes?ckey=someplayer").await;
+    assert_eq!(notes["total_count"], 1);
+    assert_eq!(notes["data"][0]["text"], "Warned for powergaming");
+
+    let notes = harness.get_json("/v2/player/notes?ckey=otherplayer").await;
+    assert_eq!(notes["total_count"], 0);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn rounds_and_activity() {
+    let harness = Harness::new().await;
+
+    let rounds = harness.get_json("/v2/player/rounds?ckey=someplayer").await;
+    assert_eq!(rounds["total_count"], 3);
+
+    let response = harness.get("/v2/player/activity?ckey=someplayer").await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let activity = harness
+        .get_json("/v2/player/activity?ckey=otherplayer")
+        .await;
+    assert_eq!(activity.as_array().unwrap().len(), 1);
+
+    let activity = harness
+        .get_json("/v2/player/activity?ckey=otherplayer&days=500")
+        .await;
+    assert_eq!(activity.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn activity_heatmap() {
+    let total = |heatmap: &serde_json::Value| -> i64 {
+        heatmap["hours"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|day| day.as_array().unwrap())
+            .map(|hour| hour.as_i64().unwrap())
+            .sum()
+    };
+
+    let harness = Harness::new().await;
+
+    let heatmap = harness
+        .get_json("/v2/player/activity/heatmap?ckey=someplayer")
+        .await;
+    assert_eq!(heatmap["days"], 180);
+    assert_eq!(heatmap["timezone"], "+00:00");
+    assert_eq!(heatmap["hours"].as_array().unwrap().len(), 7);
+    assert_eq!(heatmap["hours"][0].as_array().unwrap().len(), 24);
+    assert_eq!(total(&heatmap), 2);
+
+    let heatmap = harness
+        .get_json("/v2/player/activity/heatmap?ckey=someplayer&timezone=%2B05:30")
+        .await;
+    assert_eq!(heatmap["timezone"], "+05:30");
+    assert_eq!(total(&heatmap), 2);
+
+    let heatmap = harness
+        .get_json("/v2/player/activity/heatmap?ckey=otherplayer&days=500")
+        .await;
+    assert_eq!(total(&heatmap), 2);
+
+    let response = harness
+        .get("/v2/player/activity/heatmap?ckey=someplayer&timezone=Europe/Berlin")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness.get("/v2/player/activity/heatmap?ckey=nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn friends() {
+    let harness = Harness::new().await;
+
+    let friendship = harness
+        .post("/v2/player/add_friend?ckey=someplayer&friend=otherplayer")
+        .await
+        .into_json::<serde_json::Value>()
+        .await
+        .unwrap();
+    assert_eq!(friendship["status"], "pending");
+    let id = friendship["id"].as_i64().unwrap();
+
+    let invites = harness
+        .get_json("/v2/player/friend_invites?ckey=otherplayer")
+        .await;
+    assert_eq!(invites["received"].as_array().unwrap().len(), 1);
+
+    let response = harness
+        .post(&format!(
+            "/v2/player/accept_friend?ckey=otherplayer&friendship_id={id}"
+        ))
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let friends = harness.get_json("/v2/player/friends?ckey=someplayer").await;
+    assert_eq!(friends.as_array().unwrap().len(), 1);
+
+    let check = harness
+        .get_json("/v2/player/check_friends?ckey=otherplayer&friend=someplayer")
+        .await;
+    assert_eq!(check["status"], "accepted");
+
+    let response = harness
+        .post(&format!(
+            "/v2/player/remove_friend?ckey=someplayer&friendship_id={id}"
+        ))
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let friends = harness.get_json("/v2/player/friends?ckey=someplayer").await;
+    assert!(friends.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn lookup() {
+    let harness = Harness::new().await;
+
+    let response = harness.get("/v2/player/lookup").await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let result = harness.get_json("/v2/player/lookup?ip=10.0.0.1").await;
+    let result = result.to_string();
+    assert!(result.contains("someplayer") && result.contains("altplayer"));
+    assert!(!result.contains("otherplayer"));
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn autocomplete() {
+    let harness = Harness::new().await;
+
+    let jobs = harness.get_json("/v2/autocomplete/job?job=secu").await;
+    assert_eq!(jobs, serde_json::json!(["Security Officer"]));
+
+    let ckeys = harness.get_json("/v2/autocomplete/ckey?ckey=some").await;
+    assert_eq!(ckeys, serde_json::json!(["someplayer"]));
+
+    let names = harness
+        .get_json("/v2/autocomplete/ic_name?ic_name=jane")
+        .await;
+    assert!(names.to_string().contains("Jane Roe"));
+
+    let response = harness
+        .post("/v2/autocomplete/ckey/hide?ckey=someplayer&hid_by=1")
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let ckeys = harness.get_json("/v2/autocomplete/ckey?ckey=some").await;
+    assert_eq!(ckeys, serde_json::json!([]));
+
+    let response = harness
+        .post("/v2/autocomplete/ckey/unhide?ckey=someplayer&unhid_by=1")
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let ckeys = harness.get_json("/v2/autocomplete/ckey?ckey=some").await;
+    assert_eq!(ckeys, serde_json::json!(["someplayer"]));
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn profile() {
+    let harness = Harness::new().await;
+
+    let profile = harness
+        .get_json("/v2/player/profile?ckey=someplayer&include=roletime,characters,favorite_character,achievements")
+        .await;
+    assert_eq!(profile["player"]["ckey"], "someplayer");
+    assert_eq!(profile["roletime"].as_array().unwrap().len(), 3);
+    assert_eq!(profile["characters"].as_array().unwrap().len(), 2);
+    assert_eq!(profile["favorite_character"][0], "John Doe");
+    assert_eq!(profile["achievements"].as_array().unwrap().len(), 2);
+    assert!(profile.get("discord").is_none());
+    assert_eq!(profile["errors"], serde_json::json!({}));
+
+    let profile = harness
+        .get_json("/v2/player/profile?ckey=altplayer&include=favorite_character")
+        .await;
+    assert_eq!(
+        profile["favorite_character"],
+        serde_json::json!(["Jane Roe", "Assistant"])
+    );
+    assert!(profile.get("roletime").is_none());
+
+    let response = harness
+        .get("/v2/player/profile?ckey=someplayer&include=bogus")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness.get("/v2/player/profile?ckey=nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn department_roletime() {
+    let harness = Harness::new().await;
+
+    let departments = harness
+        .get_json("/v2/player/roletime/departments?ckey=someplayer")
+        .await;
+    assert_eq!(
+        departments,
+        serde_json::json!([{ "department": "Security", "minutes": 720 }])
+    );
+
+    let top = harness
+        .get_json("/v2/roletime/top?department=security&limit=1")
+        .await;
+    assert_eq!(
+        top,
+        serde_json::json!([{ "ckey": "someplayer", "minutes": 720 }])
+    );
+
+    let response = harness.get("/v2/roletime/top?department=clowns").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = harness.get("/v2/roletime/top").await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let suggestions = harness
+        .get_json("/v2/autocomplete/job?job=med&departments=true")
+        .await;
+    assert_eq!(
+        suggestions,
+        serde_json::json!({ "departments": ["Medical"], "jobs": ["Medical Doctor"] })
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn roletime_history() {
+    let harness = Harness::new().await;
+
+    let history = harness
+        .get_json("/v2/player/roletime/history?ckey=someplayer&job=Security%20Officer")
+        .await;
+    assert_eq!(
+        history,
+        serde_json::json!([["2024-01-01", 150], ["2024-01-08", 30]])
+    );
+
+    let history = harness
+        .get_json(
+            "/v2/player/roletime/history?ckey=someplayer&from=2024-01-02&to=2024-01-04&bucket=day",
+        )
+        .await;
+    assert_eq!(history, serde_json::json!([["2024-01-03", 110]]));
+
+    let top = harness
+        .get_json("/v2/roletime/top?job=Security%20Officer&since=2024-01-05")
+        .await;
+    assert_eq!(
+        top,
+        serde_json::json!([
+            { "ckey": "otherplayer", "minutes": 60 },
+            { "ckey": "someplayer", "minutes": 30 }
+        ])
+    );
+
+    let response = harness.get("/v2/player/roletime/history?ckey=nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = harness
+        .get("/v2/player/roletime/history?ckey=someplayer&from=last%20week")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness
+        .get("/v2/roletime/top?job=Security%20Officer&since=2024-13-01")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn lookup_graph() {
+    let harness = Harness::new().await;
+
+    let graph = harness
+        .get_json("/v2/player/lookup/graph?ckey=someplayer&depth=2")
+        .await;
+    let nodes: Vec<_> = graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| {
+            (
+                node["id"].as_str().unwrap(),
+                node["depth"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        nodes,
+        [
+            ("ckey:someplayer", 0),
+            ("ip:10.0.0.1", 1),
+            ("cid:1111111111", 1),
+            ("ckey:altplayer", 2),
+            ("cid:3333333333", 2),
+        ]
+    );
+    assert_eq!(graph["edges"].as_array().unwrap().len(), 4);
+    assert_eq!(graph["truncated"], false);
+
+    let edge = graph["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|edge| edge["source"] == "ckey:someplayer" && edge["target"] == "ip:10.0.0.1")
+        .unwrap();
+    assert_eq!(edge["connections"], 2);
+
+    let graph = harness
+        .get_json("/v2/player/lookup/graph?ckey=someplayer&max_nodes=2")
+        .await;
+    assert_eq!(graph["nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(graph["truncated"], true);
+
+    let response = harness
+        .get("/v2/player/lookup/graph?ckey=someplayer&ip=10.0.0.1")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn deaths() {
+    let harness = Harness::new().await;
+
+    let deaths = harness.get_json("/v2/player/deaths?ckey=otherplayer").await;
+    assert_eq!(deaths["total_count"], 1);
+    assert_eq!(deaths["data"][0]["last_attacker"], "John Doe");
+    assert_eq!(deaths["data"][0]["damage"]["brute"], 150);
+    assert_eq!(deaths["data"][0]["damage"]["fire"], 20);
+    assert!(deaths["data"][0].get("lakey").is_none());
+
+    let stats = harness
+        .get_json("/v2/player/deaths/stats?ckey=someplayer")
+        .await;
+    assert_eq!(
+        stats,
+        serde_json::json!({
+            "deaths": 1,
+            "suicides": 0,
+            "by_job": { "Security Officer": 1 },
+            "by_damage_type": { "fire": 1 }
+        })
+    );
+
+    let stats = harness
+        .get_json("/v2/player/deaths/stats?ckey=altplayer")
+        .await;
+    assert_eq!(stats["suicides"], 1);
+    assert_eq!(stats["by_damage_type"], serde_json::json!({ "tox": 1 }));
+
+    let response = harness.get("/v2/player/deaths?ckey=nobody").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn antagonists() {
+    let harness = Harness::new().await;
+
+    let antagonists = harness
+        .get_json("/v2/player/antagonists?ckey=someplayer")
+        .await;
+    assert_eq!(antagonists["antagonists"].as_array().unwrap().len(), 2);
+    assert_eq!(antagonists["antagonists"][0]["round_id"], 2);
+    assert_eq!(
+        antagonists["antagonists"][0]["antagonist_name"],
+        "Nuclear Operative"
+    );
+    assert_eq!(antagonists["antagonists"][1]["name"], "John Doe");
+    assert_eq!(antagonists["antagonists"][1]["success"], true);
+    assert_eq!(
+        antagonists["totals"]["/datum/antagonist/traitor"],
+        serde_json::json!({ "rounds": 1, "wins": 1, "win_rate": 1.0 })
+    );
+
+    let antagonists = harness
+        .get_json("/v2/player/antagonists?ckey=otherplayer")
+        .await;
+    assert_eq!(antagonists["antagonists"][0]["success"], false);
+    assert_eq!(
+        antagonists["totals"]["/datum/antagonist/changeling"]["win_rate"],
+        0.0
+    );
+
+    let antagonists = harness
+        .get_json("/v2/player/antagonists?ckey=adminguy")
+        .await;
+    assert_eq!(antagonists["antagonists"], serde_json::json!([]));
+}
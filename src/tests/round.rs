@@ -0,0 +1,271 @@
+use rocket::http::{Header, Status};
+use serde_json::Value;
+
+use super::harness::Harness;
+
+/// A round that never ended, with a traitor on its manifest and their death.
+const UNFINISHED_ROUND: &str = "INSERT INTO `round` (`id`, `initialize_datetime`, `start_datetime`, `server_ip`, `server_port`, `commit_hash`, `game_mode`, `map_name`, `station_name`) VALUES
+  (4, '2024-01-04 10:00:00', '2024-01-04 10:05:00', INET_ATON('203.0.113.7'), 1337, 'dddddddddddddddddddddddddddddddddddddddd', 'dynamic', 'MetaStation', 'Space Station 13');
+INSERT INTO `manifest` (`server_ip`, `server_port`, `round_id`, `ckey`, `character_name`, `job`, `special`, `latejoin`, `timestamp`) VALUES
+  (INET_ATON('203.0.113.7'), 1337, 4, 'someplayer', 'John Doe', 'Security Officer', 'Traitor', 0, '2024-01-04 10:05:00');
+INSERT INTO `death` (`pod`, `x_coord`, `y_coord`, `z_coord`, `mapname`, `server_ip`, `server_port`, `round_id`, `tod`, `job`, `special`, `name`, `byondkey`, `laname`, `lakey`, `bruteloss`, `brainloss`, `fireloss`, `oxyloss`, `toxloss`, `cloneloss`, `staminaloss`, `last_words`, `suicide`) VALUES
+  ('Brig', 90, 90, 2, 'MetaStation', INET_ATON('203.0.113.7'), 1337, 4, '2024-01-04 10:30:00', 'Security Officer', 'Traitor', 'John Doe', 'someplayer', 'Jane Roe', 'otherplayer', 0, 0, 210, 0, 0, 0, 0, NULL, 0);";
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn round() {
+    let harness = Harness::new().await;
+
+    let round = harness.get_json("/v2/round?round_id=1").await;
+    assert_eq!(round["map_name"], "MetaStation");
+    assert_eq!(round["dynamic_tier"], 2);
+    assert_eq!(round["storyteller"], "Default");
+    assert_eq!(round["antagonists"].as_array().unwrap().len(), 2);
+
+    let response = harness.get("/v2/round?round_id=404").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn rounds() {
+    let harness = Harness::new().await;
+
+    let rounds = harness.get_json("/v2/rounds?fetch_size=2").await;
+    assert_eq!(rounds["total_count"], 3);
+    let data = rounds["data"].as_array().unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0]["round_id"], 3);
+
+    let rounds = harness.get_json("/v2/rounds?round_id=2").await;
+    assert_eq!(rounds["data"][0]["round_id"], 2);
+    assert_eq!(rounds["data"][0]["game_mode"], "dynamic");
+    assert_eq!(rounds["data"][0]["end_state"], "nuke");
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn round_filters() {
+    let round_ids = |rounds: &Value| -> Vec<i64> {
+        rounds["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|round| round["round_id"].as_i64().unwrap())
+            .collect()
+    };
+
+    let harness = Harness::new().await;
+
+    for (query, expected) in [
+        ("map=MetaStation", vec![3, 1]),
+        ("server=test%20station", vec![2, 1]),
+        ("storyteller=Default", vec![1]),
+        ("dynamic_tier=4", vec![2]),
+        ("end_state=nuke", vec![2]),
+        ("game_mode=dynamic&from=2024-01-02", vec![3, 2]),
+        ("to=2024-01-02", vec![1]),
+        ("min_duration=100", vec![3]),
+        ("min_population=30", vec![3, 1]),
+        ("sort=oldest", vec![1, 2, 3]),
+        ("sort=shortest", vec![2, 1, 3]),
+        ("sort=population", vec![3, 1, 2]),
+    ] {
+        let rounds = harness.get_json(&format!("/v2/rounds?{query}")).await;
+        assert_eq!(round_ids(&rounds), expected, "{query}");
+        assert_eq!(rounds["total_count"], expected.len(), "{query}");
+    }
+
+    let response = harness.get("/v2/rounds?server=Nowhere").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = harness.get("/v2/rounds?from=2024-1-1x").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn server_status() {
+    let harness = Harness::new().await;
+
+    let servers = harness.get_json("/v2/server").await;
+    let servers = servers.as_array().unwrap();
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0]["name"], "Test Station");
+
+    let response = harness.get("/recent-test-merges.json").await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn feedback() {
+    let harness = Harness::new().await;
+
+    let keys = harness.get_json("/v2/round/feedback?round_id=1").await;
+    let keys: Vec<_> = keys
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|key| key["key_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "antagonists",
+            "dynamic_tier",
+            "round_end_stats",
+            "storyteller",
+            "testmerged_prs"
+        ]
+    );
+
+    let feedback = harness
+        .get_json("/v2/round/feedback?round_id=1&key=antagonists")
+        .await;
+    assert_eq!(feedback["key_type"], "associative");
+    assert_eq!(feedback["data"][0]["key"], "someplayer");
+    assert_eq!(feedback["data"].as_array().unwrap().len(), 2);
+
+    let response = harness
+        .get("/v2/round/feedback?round_id=1&key=roundend_nukedisk")
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = harness.get("/v2/round/feedback?round_id=404").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let series = harness
+        .get_json("/v2/feedback/series?key=round_end_stats&path=data.players.total")
+        .await;
+    assert_eq!(
+        series
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|point| (
+                point["round_id"].as_i64().unwrap(),
+                point["value"].as_i64().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        [(1, 30), (2, 15), (3, 40)]
+    );
+
+    let response = harness
+        .get("/v2/feedback/series?key=round_end_stats&path=data.players')")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness
+        .get("/v2/feedback/series?key=round_end_stats&path=data.players&from=1%20day%20ago")
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = harness
+        .get("/v2/feedback/series?key=roundend_nukedisk&path=data.1.holder")
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn manifest() {
+    let harness = Harness::new().await;
+
+    let response = harness
+        .post("/v2/autocomplete/ckey/hide?ckey=someplayer&hid_by=1")
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let manifest = harness.get_json("/v2/round/manifest?round_id=1").await;
+    let manifest = manifest.as_array().unwrap();
+    assert_eq!(manifest.len(), 3);
+    assert_eq!(manifest[0]["character_name"], "John Doe");
+    assert_eq!(manifest[0]["ckey"], "someplayer");
+    assert_eq!(manifest[2]["latejoin"], true);
+
+    let response = harness
+        .client
+        .get("/v2/round/manifest?round_id=1")
+        .header(Header::new("X-EXP-KEY", "test-exposed-secret"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let manifest: Value = response.into_json().await.unwrap();
+    assert_eq!(manifest[0]["ckey"], Value::Null);
+    assert_eq!(manifest[1]["ckey"], "otherplayer");
+
+    let response = harness.get("/v2/round/manifest?round_id=404").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    harness.execute(UNFINISHED_ROUND).await;
+
+    let response = harness.get("/v2/round/manifest?round_id=4").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn timeline() {
+    let harness = Harness::new().await;
+
+    let timeline = harness.get_json("/v2/round/timeline?round_id=1").await;
+    let timeline = timeline.as_array().unwrap();
+    let kinds: Vec<_> = timeline
+        .iter()
+        .map(|item| item["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "round_initialized",
+            "population",
+            "round_started",
+            "population",
+            "citation",
+            "crime",
+            "population",
+            "death",
+            "ticket_opened",
+            "ticket_opened",
+            "death",
+            "achievement_unlocked",
+            "round_ended"
+        ]
+    );
+    assert_eq!(timeline[2]["offset"], "00:10:00");
+    assert_eq!(timeline[4]["fine"], 200);
+    assert_eq!(timeline[7]["name"], "Jane Roe");
+    assert_eq!(timeline[7]["last_attacker"], "John Doe");
+    assert_eq!(timeline[11]["ckey"], "someplayer");
+    assert_eq!(timeline[12]["offset"], "01:35:00");
+
+    let response = harness
+        .post("/v2/autocomplete/ckey/hide?ckey=someplayer&hid_by=1")
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = harness
+        .client
+        .get("/v2/round/timeline?round_id=1")
+        .header(Header::new("X-EXP-KEY", "test-exposed-secret"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let timeline: Value = response.into_json().await.unwrap();
+    assert_eq!(timeline[8]["sender"], "otherplayer");
+    assert_eq!(timeline[9]["sender"], "adminguy");
+    assert_eq!(timeline[9]["recipient"], Value::Null);
+    assert_eq!(timeline[11]["ckey"], Value::Null);
+
+    let timeline = harness.get_json("/v2/round/timeline?round_id=1").await;
+    assert_eq!(timeline[11]["ckey"], "someplayer");
+
+    let response = harness.get("/v2/round/timeline?round_id=404").await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    harness.execute(UNFINISHED_ROUND).await;
+
+    let response = harness.get("/v2/round/timeline?round_id=4").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
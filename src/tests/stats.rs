@@ -0,0 +1,166 @@
+use rocket::http::Status;
+use serde_json::json;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn moderation() {
+    let harness = Harness::new().await;
+
+    let weeks = harness
+        .get_json("/v2/stats/moderation?from=2024-01-01")
+        .await;
+    assert_eq!(
+        weeks,
+        json!([{
+            "week": "2024-01-01",
+            "bans_by_admin": { "adminguy": 3 },
+            "bans_by_role": { "Security Officer": 1, "Server": 2, "Warden": 1 },
+            "bans_by_duration": { "over_month": 1, "permanent": 1, "up_to_week": 1 },
+            "notes_by_admin": { "adminguy": 2 },
+            "notes_by_severity": { "high": 1, "minor": 1 },
+            "tickets": [{ "admin": "adminguy", "handled": 1, "median_first_response": 120 }]
+        }])
+    );
+
+    let weeks = harness
+        .get_json("/v2/stats/moderation?from=2024-02-01")
+        .await;
+    assert_eq!(weeks, json!([]));
+
+    // without a range only the last few weeks are covered, which the fixtures predate
+    let weeks = harness.get_json("/v2/stats/moderation").await;
+    assert_eq!(weeks, json!([]));
+
+    let response = harness.get("/v2/stats/moderation?to=soon").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn maps() {
+    let harness = Harness::new().await;
+
+    let maps = harness.get_json("/v2/stats/maps?from=2024-01-01").await;
+    assert_eq!(
+        maps,
+        json!([
+            {
+                "map_name": "IceBoxStation",
+                "rounds": 1,
+                "average_duration": 55.0,
+                "median_duration": 55,
+                "average_peak_population": 15.0,
+                "deaths_per_round": 1.0,
+                "shuttles": { "None": 1 },
+                "end_states": { "nuke": 1 },
+                "average_dynamic_tier": 4.0,
+                "servers": {
+                    "Test Station": {
+                        "rounds": 1,
+                        "average_duration": 55.0,
+                        "median_duration": 55,
+                        "average_peak_population": 15.0,
+                        "deaths_per_round": 1.0,
+                        "shuttles": { "None": 1 },
+                        "end_states": { "nuke": 1 },
+                        "average_dynamic_tier": 4.0
+                    }
+                }
+            },
+            {
+                "map_name": "MetaStation",
+                "rounds": 2,
+                "average_duration": 98.5,
+                "median_duration": 98,
+                "average_peak_population": 37.5,
+                "deaths_per_round": 1.5,
+                "shuttles": { "Birdboat Emergency Shuttle": 1, "Standard Emergency Shuttle": 1 },
+                "end_states": { "proper completion": 2 },
+                "average_dynamic_tier": 1.5,
+                "servers": {
+                    "Test Station": {
+                        "rounds": 1,
+                        "average_duration": 85.0,
+                        "median_duration": 85,
+                        "average_peak_population": 35.0,
+                        "deaths_per_round": 2.0,
+                        "shuttles": { "Standard Emergency Shuttle": 1 },
+                        "end_states": { "proper completion": 1 },
+                        "average_dynamic_tier": 2.0
+                    }
+                }
+            }
+        ])
+    );
+
+    let maps = harness.get_json("/v2/stats/maps?from=2024-01-03").await;
+    assert_eq!(maps[0]["map_name"], "MetaStation");
+    assert_eq!(maps[0]["rounds"], 1);
+    assert_eq!(maps[0]["servers"], json!({}));
+
+    let maps = harness.get_json("/v2/stats/maps").await;
+    assert_eq!(maps, json!([]));
+
+    let response = harness.get("/v2/stats/maps?from=2024-01-03&to=never").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn antagonists() {
+    let harness = Harness::new().await;
+
+    let stats = harness
+        .get_json("/v2/stats/antagonists?from=2024-01-01")
+        .await;
+    assert_eq!(stats["rounds"], 3);
+    assert_eq!(
+        stats["antagonist_types"],
+        json!({
+            "/datum/antagonist/changeling": {
+                "antagonists": 1,
+                "rounds": 1,
+                "objective_success_rate": 0.0,
+                "win_rate": 0.0
+            },
+            "/datum/antagonist/nukeop": {
+                "antagonists": 1,
+                "rounds": 1,
+                "objective_success_rate": 1.0,
+                "win_rate": 1.0
+            },
+            "/datum/antagonist/traitor": {
+                "antagonists": 1,
+                "rounds": 1,
+                "objective_success_rate": 1.0,
+                "win_rate": 1.0
+            }
+        })
+    );
+    assert_eq!(stats["antagonists_per_round"], 1.5);
+    assert_eq!(stats["readied_players_per_round"], 22.5);
+    assert_eq!(
+        stats["weeks"],
+        json!([{
+            "week": "2024-01-01",
+            "rounds": 3,
+            "dynamic_tiers": { "1": 1, "2": 1, "4": 1 },
+            "storytellers": { "Default": 1 }
+        }])
+    );
+
+    let stats = harness
+        .get_json("/v2/stats/antagonists?from=2024-01-03")
+        .await;
+    assert_eq!(stats["rounds"], 1);
+    assert_eq!(stats["antagonist_types"], json!({}));
+    assert_eq!(stats["antagonists_per_round"], json!(null));
+
+    let stats = harness.get_json("/v2/stats/antagonists").await;
+    assert_eq!(stats["rounds"], 0);
+
+    let response = harness.get("/v2/stats/antagonists?from=03/01/2024").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
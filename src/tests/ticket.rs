@@ -0,0 +1,48 @@
+use rocket::http::Status;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn search() {
+    let harness = Harness::new().await;
+
+    let tickets = harness.get_json("/v2/tickets").await;
+    assert_eq!(tickets["total_count"], 2);
+
+    let newest = &tickets["data"][0];
+    assert_eq!(newest["ticket_id"], 2);
+    assert_eq!(newest["opened_by"], "adminguy");
+    assert_eq!(newest["recipient"], "someplayer");
+    assert_eq!(newest["first_response"], serde_json::Value::Null);
+    assert_eq!(newest["state"], "closed");
+    assert_eq!(newest["message_count"], 2);
+
+    let oldest = &tickets["data"][1];
+    assert_eq!(oldest["ticket_id"], 1);
+    assert_eq!(oldest["first_response"], 120);
+    assert_eq!(oldest["state"], "resolved");
+    assert_eq!(oldest["message_count"], 2);
+    assert_eq!(oldest["logs"].as_array().unwrap().len(), 3);
+
+    let tickets = harness
+        .get_json("/v2/tickets?action=Resolved&admin=adminguy")
+        .await;
+    assert_eq!(tickets["total_count"], 1);
+    assert_eq!(tickets["data"][0]["ticket_id"], 1);
+
+    let tickets = harness.get_json("/v2/tickets?search=attacked").await;
+    assert_eq!(tickets["total_count"], 1);
+    assert_eq!(tickets["data"][0]["ticket_id"], 2);
+
+    let tickets = harness
+        .get_json("/v2/tickets?round_id=2&from=2024-01-01")
+        .await;
+    assert_eq!(tickets["total_count"], 0);
+
+    let tickets = harness.get_json("/v2/tickets?search=%25").await;
+    assert_eq!(tickets["total_count"], 0);
+
+    let response = harness.get("/v2/tickets?to=2024-01-32").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
@@ -0,0 +1,40 @@
+use rocket::http::Status;
+use serde_json::json;
+
+use super::harness::Harness;
+
+#[tokio::test]
+#[ignore = "requires a MySQL/MariaDB server"]
+async fn verify_and_unverify() {
+    let harness = Harness::new().await;
+
+    let response = harness
+        .post_json(
+            "/v2/verify",
+            json!({ "discord_id": "1001", "ckey": "otherplayer" }),
+        )
+        .await;
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(
+        response.into_json::<serde_json::Value>().await.unwrap(),
+        "someplayer"
+    );
+
+    let response = harness
+        .post_json("/v2/unverify", json!({ "ckey": "someplayer" }))
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = harness
+        .post_json("/v2/unverify", json!({ "ckey": "someplayer" }))
+        .await;
+    assert_eq!(response.status(), Status::Conflict);
+
+    let response = harness
+        .post_json(
+            "/v2/unverify",
+            json!({ "discord_id": "1", "ckey": "someplayer" }),
+        )
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
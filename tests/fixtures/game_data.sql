@@ -0,0 +1,115 @@
+-- Deterministic data loaded on top of game_schema.sql by the test harness.
+-- Statements are split on a semicolon at the end of a line.
+-- Rows are logged under the servers' public address, not the loopback topic address.
+
+INSERT INTO `player` (`ckey`, `byond_key`, `firstseen`, `firstseen_round_id`, `lastseen`, `lastseen_round_id`, `ip`, `computerid`, `accountjoindate`) VALUES
+  ('someplayer', 'Some Player', '2024-01-01 10:00:00', 1, NOW() - INTERVAL 1 DAY, 3, INET_ATON('10.0.0.1'), '1111111111', '2015-06-01'),
+  ('otherplayer', 'OtherPlayer', '2024-01-01 10:05:00', 1, '2024-01-02 12:00:00', 2, INET_ATON('10.0.0.2'), '2222222222', '2018-02-01'),
+  ('altplayer', 'AltPlayer', '2024-01-02 09:00:00', 2, '2024-01-02 11:00:00', 2, INET_ATON('10.0.0.1'), '3333333333', NULL),
+  ('adminguy', 'AdminGuy', '2023-01-01 00:00:00', 1, NOW() - INTERVAL 1 DAY, 3, INET_ATON('10.0.0.9'), '9999999999', '2010-01-01');
+
+INSERT INTO `round` (`id`, `initialize_datetime`, `start_datetime`, `shutdown_datetime`, `end_datetime`, `server_ip`, `server_port`, `commit_hash`, `game_mode`, `game_mode_result`, `end_state`, `shuttle_name`, `map_name`, `station_name`) VALUES
+  (1, '2024-01-01 10:00:00', '2024-01-01 10:10:00', '2024-01-01 11:40:00', '2024-01-01 11:35:00', INET_ATON('203.0.113.7'), 1337, 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa', 'dynamic', 'undefined', 'proper completion', 'Standard Emergency Shuttle', 'MetaStation', 'Space Station 13'),
+  (2, '2024-01-02 10:00:00', '2024-01-02 10:05:00', '2024-01-02 11:05:00', '2024-01-02 11:00:00', INET_ATON('203.0.113.7'), 1337, 'bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb', 'dynamic', 'undefined', 'nuke', NULL, 'IceBoxStation', 'Frosty Station'),
+  (3, '2024-01-03 10:00:00', '2024-01-03 10:08:00', '2024-01-03 12:10:00', '2024-01-03 12:00:00', INET_ATON('203.0.113.7'), 7331, 'cccccccccccccccccccccccccccccccccccccccc', 'dynamic', 'undefined', 'proper completion', 'Birdboat Emergency Shuttle', 'MetaStation', 'Space Station 13');
+
+INSERT INTO `legacy_population` (`playercount`, `admincount`, `time`, `server_ip`, `server_port`, `round_id`) VALUES
+  (20, 2, '2024-01-01 10:00:00', INET_ATON('203.0.113.7'), 1337, 1),
+  (35, 2, '2024-01-01 10:30:00', INET_ATON('203.0.113.7'), 1337, 1),
+  (30, 1, '2024-01-01 11:00:00', INET_ATON('203.0.113.7'), 1337, 1),
+  (15, 1, '2024-01-02 10:30:00', INET_ATON('203.0.113.7'), 1337, 2),
+  (40, 3, '2024-01-03 11:00:00', INET_ATON('203.0.113.7'), 7331, 3);
+
+INSERT INTO `feedback` (`datetime`, `round_id`, `key_name`, `key_type`, `version`, `json`) VALUES
+  ('2024-01-01 11:35:00', 1, 'dynamic_tier', 'associative', 1, '{"data":{"1":{"tier":"2","player_count":"30"}}}'),
+  ('2024-01-01 11:35:00', 1, 'storyteller', 'associative', 1, '{"data":{"1":{"name":"Default"}}}'),
+  ('2024-01-01 11:35:00', 1, 'roundend_nukedisk', 'associative', 1, '{"data":{"1":{"holder":"John Doe","x":"100","y":"100","z":"2"}}}'),
+  ('2024-01-01 11:35:00', 1, 'antagonists', 'associative', 1, '{"data":{"1":{"key":"someplayer","name":"John Doe","antagonist_name":"Traitor","antagonist_type":"/datum/antagonist/traitor","objectives":[{"text":"Steal the captain''s antique laser gun","objective_type":"/datum/objective/steal","result":"SUCCESS"}]},"2":{"key":"otherplayer","name":"Jane Roe","antagonist_name":"Changeling","antagonist_type":"/datum/antagonist/changeling","objectives":[{"text":"Escape alive","objective_type":"/datum/objective/escape","result":"FAIL"}]}}}'),
+  ('2024-01-01 11:35:00', 1, 'round_end_stats', 'nested tally', 1, '{"data":{"players":{"total":30,"dead":4}}}'),
+  ('2024-01-01 11:35:00', 1, 'testmerged_prs', 'associative', 1, '{"data":{"1":{"number":"101","title":"First test merge"},"2":{"number":"102","title":"Second test merge"}}}'),
+  ('2024-01-02 11:00:00', 2, 'dynamic_tier', 'associative', 1, '{"data":{"1":{"tier":"4","player_count":"15"}}}'),
+  ('2024-01-02 11:00:00', 2, 'antagonists', 'associative', 1, '{"data":{"1":{"key":"someplayer","name":"Agent Orange","antagonist_name":"Nuclear Operative","antagonist_type":"/datum/antagonist/nukeop","objectives":[{"text":"Destroy the station","objective_type":"/datum/objective/nuclear","result":"SUCCESS"}]}}}'),
+  ('2024-01-02 11:00:00', 2, 'round_end_stats', 'nested tally', 1, '{"data":{"players":{"total":15,"dead":15}}}'),
+  ('2024-01-03 12:00:00', 3, 'dynamic_tier', 'associative', 1, '{"data":{"1":{"tier":"1","player_count":"40"}}}'),
+  ('2024-01-03 12:00:00', 3, 'round_end_stats', 'nested tally', 1, '{"data":{"players":{"total":40,"dead":2}}}');
+
+INSERT INTO `manifest` (`server_ip`, `server_port`, `round_id`, `ckey`, `character_name`, `job`, `special`, `latejoin`, `timestamp`) VALUES
+  (INET_ATON('203.0.113.7'), 1337, 1, 'someplayer', 'John Doe', 'Security Officer', 'Traitor', 0, '2024-01-01 10:10:00'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 'otherplayer', 'Jane Roe', 'Medical Doctor', 'Changeling', 0, '2024-01-01 10:10:00'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 'adminguy', 'Admin Person', 'Captain', 'NONE', 1, '2024-01-01 10:20:00'),
+  (INET_ATON('203.0.113.7'), 1337, 2, 'someplayer', 'Agent Orange', 'Operative', 'Operative', 0, '2024-01-02 10:05:00'),
+  (INET_ATON('203.0.113.7'), 1337, 2, 'altplayer', 'Jane Roe', 'Assistant', 'NONE', 1, '2024-01-02 10:15:00'),
+  (INET_ATON('203.0.113.7'), 7331, 3, 'someplayer', 'John Doe', 'Security Officer', 'NONE', 0, '2024-01-03 10:08:00'),
+  (INET_ATON('203.0.113.7'), 7331, 3, 'otherplayer', 'Jane Roe', 'Medical Doctor', 'NONE', 0, '2024-01-03 10:08:00');
+
+INSERT INTO `role_time` (`ckey`, `job`, `minutes`) VALUES
+  ('someplayer', 'Living', 900),
+  ('someplayer', 'Security Officer', 600),
+  ('someplayer', 'Warden', 120),
+  ('otherplayer', 'Living', 500),
+  ('otherplayer', 'Medical Doctor', 400),
+  ('otherplayer', 'Security Officer', 60),
+  ('altplayer', 'Assistant', 30),
+  ('adminguy', 'Captain', 2000);
+
+INSERT INTO `role_time_log` (`ckey`, `job`, `delta`, `datetime`) VALUES
+  ('someplayer', 'Security Officer', 60, '2024-01-01 11:35:00'),
+  ('someplayer', 'Security Officer', 90, '2024-01-03 12:00:00'),
+  ('someplayer', 'Warden', 20, '2024-01-03 12:00:00'),
+  ('someplayer', 'Security Officer', 30, '2024-01-10 12:00:00'),
+  ('otherplayer', 'Security Officer', 60, '2024-01-08 12:00:00'),
+  ('otherplayer', 'Medical Doctor', 100, '2024-01-08 12:00:00');
+
+INSERT INTO `death` (`pod`, `x_coord`, `y_coord`, `z_coord`, `mapname`, `server_ip`, `server_port`, `round_id`, `tod`, `job`, `special`, `name`, `byondkey`, `laname`, `lakey`, `bruteloss`, `brainloss`, `fireloss`, `oxyloss`, `toxloss`, `cloneloss`, `staminaloss`, `last_words`, `suicide`) VALUES
+  ('Medbay', 100, 120, 2, 'MetaStation', INET_ATON('203.0.113.7'), 1337, 1, '2024-01-01 11:00:00', 'Medical Doctor', 'Changeling', 'Jane Roe', 'otherplayer', 'John Doe', 'someplayer', 150, 0, 20, 0, 0, 0, 0, 'Why', 0),
+  ('Bridge', 110, 130, 2, 'MetaStation', INET_ATON('203.0.113.7'), 1337, 1, '2024-01-01 11:20:00', 'Captain', NULL, 'Admin Person', 'adminguy', 'John Doe', 'someplayer', 80, 0, 0, 120, 0, 0, 0, NULL, 0),
+  ('Arrivals', 50, 60, 2, 'IceBoxStation', INET_ATON('203.0.113.7'), 1337, 2, '2024-01-02 10:30:00', 'Assistant', NULL, 'Jane Roe', 'altplayer', NULL, NULL, 0, 0, 0, 0, 200, 0, 0, 'Goodbye', 1),
+  ('Brig', 90, 90, 2, 'MetaStation', INET_ATON('203.0.113.7'), 7331, 3, '2024-01-03 11:30:00', 'Security Officer', NULL, 'John Doe', 'someplayer', 'Jane Roe', 'otherplayer', 0, 0, 210, 0, 0, 0, 0, NULL, 0);
+
+INSERT INTO `citation` (`server_ip`, `server_port`, `round_id`, `sender`, `sender_ic`, `recipient`, `crime`, `crime_desc`, `fine`, `paid`, `timestamp`) VALUES
+  (INET_ATON('203.0.113.7'), 1337, 1, 'someplayer', 'John Doe', 'Jane Roe', 'Trespassing', 'Entered the armory', 200, 0, '2024-01-01 10:40:00'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 'someplayer', 'John Doe', 'Admin Person', 'Assault', NULL, NULL, 0, '2024-01-01 10:50:00'),
+  (INET_ATON('203.0.113.7'), 7331, 3, 'otherplayer', 'Jane Roe', 'John Doe', 'Littering', NULL, 50, 50, '2024-01-03 10:30:00');
+
+INSERT INTO `messages` (`type`, `targetckey`, `adminckey`, `text`, `timestamp`, `server`, `server_ip`, `server_port`, `round_id`, `secret`, `expire_timestamp`, `severity`, `playtime`, `lasteditor`, `deleted`) VALUES
+  ('note', 'someplayer', 'adminguy', 'Warned for powergaming', '2024-01-01 11:00:00', 'Primary Station', INET_ATON('203.0.113.7'), 1337, 1, 0, NULL, 'minor', 900, NULL, 0),
+  ('note', 'someplayer', 'adminguy', 'Secret note', '2024-01-01 11:05:00', 'Primary Station', INET_ATON('203.0.113.7'), 1337, 1, 1, NULL, 'high', 900, NULL, 0),
+  ('message', 'someplayer', 'adminguy', 'Please read the rules', '2024-01-02 10:30:00', 'Primary Station', INET_ATON('203.0.113.7'), 1337, 2, 0, NULL, NULL, 950, NULL, 0),
+  ('note', 'otherplayer', 'adminguy', 'Deleted note', '2024-01-02 10:30:00', 'Primary Station', INET_ATON('203.0.113.7'), 1337, 2, 0, NULL, 'none', 500, NULL, 1);
+
+INSERT INTO `ticket` (`server_ip`, `server_port`, `round_id`, `ticket`, `action`, `message`, `timestamp`, `recipient`, `sender`) VALUES
+  (INET_ATON('203.0.113.7'), 1337, 1, 1, 'Ticket Opened', 'I was killed for no reason', '2024-01-01 11:01:00', NULL, 'otherplayer'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 1, 'Reply', 'Looking into it', '2024-01-01 11:03:00', 'otherplayer', 'adminguy'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 1, 'Disconnected', 'Client disconnected', '2024-01-01 11:04:00', NULL, 'otherplayer'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 1, 'Resolved', 'Resolved', '2024-01-01 11:10:00', NULL, 'adminguy'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 2, 'Ticket Opened', 'Why did you kill them', '2024-01-01 11:05:00', 'someplayer', 'adminguy'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 2, 'Reply', 'They attacked me first', '2024-01-01 11:06:00', 'adminguy', 'someplayer'),
+  (INET_ATON('203.0.113.7'), 1337, 1, 2, 'Closed', 'Closed', '2024-01-01 11:08:00', NULL, 'adminguy');
+
+INSERT INTO `ban` (`bantime`, `server_ip`, `server_port`, `round_id`, `role`, `expiration_time`, `applies_to_admins`, `reason`, `ckey`, `ip`, `computerid`, `a_ckey`, `a_ip`, `a_computerid`, `who`, `adminwho`, `edits`, `unbanned_datetime`, `unbanned_ckey`) VALUES
+  ('2024-01-01 11:30:00', INET_ATON('203.0.113.7'), 1337, 1, 'Security Officer', NULL, 0, 'Validhunting', 'someplayer', INET_ATON('10.0.0.1'), '1111111111', 'adminguy', INET_ATON('10.0.0.9'), '9999999999', '', '', NULL, NULL, NULL),
+  ('2024-01-01 11:30:00', INET_ATON('203.0.113.7'), 1337, 1, 'Warden', NULL, 0, 'Validhunting', 'someplayer', INET_ATON('10.0.0.1'), '1111111111', 'adminguy', INET_ATON('10.0.0.9'), '9999999999', '', '', NULL, NULL, NULL),
+  ('2024-01-02 11:10:00', INET_ATON('203.0.113.7'), 1337, 2, 'Server', '2024-01-05 11:10:00', 0, 'Griefing', 'otherplayer', INET_ATON('10.0.0.2'), '2222222222', 'adminguy', INET_ATON('10.0.0.9'), '9999999999', '', '', 'AdminGuy edited the following Duration: 1440 MINUTES to 3 DAY<hr>', '2024-01-03 09:00:00', 'adminguy'),
+  ('2024-01-03 11:45:00', INET_ATON('203.0.113.7'), 7331, 3, 'Server', '2099-01-01 00:00:00', 0, 'Ban evasion', 'altplayer', INET_ATON('10.0.0.1'), '3333333333', 'adminguy', INET_ATON('10.0.0.9'), '9999999999', '', '', NULL, NULL, NULL);
+
+INSERT INTO `connection_log` (`datetime`, `server_ip`, `server_port`, `round_id`, `ckey`, `ip`, `computerid`) VALUES
+  (NOW() - INTERVAL 2 DAY, INET_ATON('203.0.113.7'), 1337, 1, 'someplayer', INET_ATON('10.0.0.1'), '1111111111'),
+  (NOW() - INTERVAL 1 DAY, INET_ATON('203.0.113.7'), 1337, 2, 'someplayer', INET_ATON('10.0.0.1'), '1111111111'),
+  (NOW() - INTERVAL 1 DAY, INET_ATON('203.0.113.7'), 1337, 2, 'altplayer', INET_ATON('10.0.0.1'), '3333333333'),
+  (NOW() - INTERVAL 1 DAY, INET_ATON('203.0.113.7'), 1337, 2, 'otherplayer', INET_ATON('10.0.0.2'), '2222222222'),
+  (NOW() - INTERVAL 400 DAY, INET_ATON('203.0.113.7'), 1337, 1, 'otherplayer', INET_ATON('10.0.0.2'), '2222222222');
+
+INSERT INTO `achievement_metadata` (`achievement_key`, `achievement_version`, `achievement_type`, `achievement_name`, `achievement_description`) VALUES
+  ('Clean Shift', 1, 'achievement', 'Clean Shift', 'Finish a shift without dying'),
+  ('Meteors', 1, 'achievement', 'Meteor Survivor', 'Survive a meteor storm'),
+  ('Tendril Score', 1, 'score', 'Tendrils Destroyed', 'Tendrils destroyed');
+
+INSERT INTO `achievements` (`ckey`, `achievement_key`, `value`, `last_updated`) VALUES
+  ('someplayer', 'Clean Shift', 1, '2024-01-01 11:35:00'),
+  ('someplayer', 'Tendril Score', 7, '2024-01-03 12:00:00'),
+  ('otherplayer', 'Clean Shift', 1, '2024-01-03 12:00:00'),
+  ('otherplayer', 'Tendril Score', 3, '2024-01-03 12:00:00');
+
+INSERT INTO `discord_links` (`ckey`, `discord_id`, `timestamp`, `one_time_token`, `valid`) VALUES
+  ('someplayer', 1001, '2024-01-01 12:00:00', '111-111', 1),
+  ('otherplayer', NULL, '2024-01-02 12:00:00', '222-222', 0);
@@ -0,0 +1,277 @@
+-- Subset of the tgstation game schema read by this crate, used by benchmarks and tests.
+
+DROP TABLE IF EXISTS `player`;
+CREATE TABLE `player` (
+  `ckey` VARCHAR(32) NOT NULL,
+  `byond_key` VARCHAR(32) DEFAULT NULL,
+  `firstseen` DATETIME NOT NULL,
+  `firstseen_round_id` INT(11) UNSIGNED NULL,
+  `lastseen` DATETIME NOT NULL,
+  `lastseen_round_id` INT(11) UNSIGNED NULL,
+  `ip` INT(10) UNSIGNED NOT NULL,
+  `computerid` VARCHAR(32) NOT NULL,
+  `uuid` VARCHAR(64) NULL,
+  `lastadminrank` VARCHAR(32) NOT NULL DEFAULT 'Player',
+  `accountjoindate` DATE DEFAULT NULL,
+  `flags` SMALLINT(5) UNSIGNED DEFAULT '0' NOT NULL,
+  PRIMARY KEY (`ckey`),
+  KEY `idx_player_cid_ckey` (`computerid`, `ckey`),
+  KEY `idx_player_ip_ckey` (`ip`, `ckey`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `role_time`;
+CREATE TABLE `role_time` (
+  `ckey` VARCHAR(32) NOT NULL,
+  `job` VARCHAR(32) NOT NULL,
+  `minutes` INT UNSIGNED NOT NULL,
+  PRIMARY KEY (`ckey`, `job`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `role_time_log`;
+CREATE TABLE `role_time_log` (
+  `id` BIGINT(20) NOT NULL AUTO_INCREMENT,
+  `ckey` VARCHAR(32) NOT NULL,
+  `job` VARCHAR(128) NOT NULL,
+  `delta` INT(11) NOT NULL,
+  `datetime` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `ckey` (`ckey`),
+  KEY `job` (`job`),
+  KEY `datetime` (`datetime`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `manifest`;
+CREATE TABLE `manifest` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) NOT NULL,
+  `ckey` VARCHAR(32) NOT NULL,
+  `character_name` VARCHAR(64) NOT NULL,
+  `job` VARCHAR(32) NOT NULL,
+  `special` VARCHAR(32) DEFAULT NULL,
+  `latejoin` TINYINT(1) NOT NULL DEFAULT 0,
+  `timestamp` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_manifest_ckey` (`ckey`, `timestamp`),
+  KEY `idx_manifest_round` (`round_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `messages`;
+CREATE TABLE `messages` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `type` ENUM('memo','message','message sent','note','watchlist entry') NOT NULL,
+  `targetckey` VARCHAR(32) NOT NULL,
+  `adminckey` VARCHAR(32) NOT NULL,
+  `text` VARCHAR(2048) NOT NULL,
+  `timestamp` DATETIME NOT NULL,
+  `server` VARCHAR(32) DEFAULT NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NULL,
+  `secret` TINYINT(1) UNSIGNED NOT NULL,
+  `expire_timestamp` DATETIME NULL DEFAULT NULL,
+  `severity` ENUM('high','medium','minor','none') DEFAULT NULL,
+  `playtime` INT(11) UNSIGNED NULL DEFAULT NULL,
+  `lasteditor` VARCHAR(32) DEFAULT NULL,
+  `edits` TEXT,
+  `deleted` TINYINT(1) UNSIGNED NOT NULL DEFAULT '0',
+  `deleted_ckey` VARCHAR(32) NULL DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_msg_ckey_time` (`targetckey`, `timestamp`, `deleted`),
+  KEY `idx_msg_type_ckeys_time` (`type`, `targetckey`, `adminckey`, `timestamp`, `deleted`),
+  KEY `idx_msg_type_ckey_time_odr` (`type`, `targetckey`, `timestamp`, `deleted`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `ban`;
+CREATE TABLE `ban` (
+  `id` INT(11) UNSIGNED NOT NULL AUTO_INCREMENT,
+  `bantime` DATETIME NOT NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NULL,
+  `role` VARCHAR(32) NULL DEFAULT NULL,
+  `expiration_time` DATETIME NULL DEFAULT NULL,
+  `applies_to_admins` TINYINT(1) UNSIGNED NOT NULL DEFAULT '0',
+  `reason` VARCHAR(2048) NOT NULL,
+  `ckey` VARCHAR(32) NULL DEFAULT NULL,
+  `ip` INT(10) UNSIGNED NULL DEFAULT NULL,
+  `computerid` VARCHAR(32) NULL DEFAULT NULL,
+  `a_ckey` VARCHAR(32) NOT NULL,
+  `a_ip` INT(10) UNSIGNED NOT NULL,
+  `a_computerid` VARCHAR(32) NOT NULL,
+  `who` VARCHAR(2048) NOT NULL,
+  `adminwho` VARCHAR(2048) NOT NULL,
+  `edits` TEXT NULL DEFAULT NULL,
+  `unbanned_datetime` DATETIME NULL DEFAULT NULL,
+  `unbanned_ckey` VARCHAR(32) NULL DEFAULT NULL,
+  `unbanned_ip` INT(10) UNSIGNED NULL DEFAULT NULL,
+  `unbanned_computerid` VARCHAR(32) NULL DEFAULT NULL,
+  `unbanned_round_id` INT(11) UNSIGNED NULL DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_ban_isbanned` (`ckey`, `role`, `unbanned_datetime`, `expiration_time`),
+  KEY `idx_ban_isbanned_details` (`ckey`, `ip`, `computerid`, `role`, `unbanned_datetime`, `expiration_time`),
+  KEY `idx_ban_count` (`bantime`, `a_ckey`, `applies_to_admins`, `unbanned_datetime`, `expiration_time`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `ticket`;
+CREATE TABLE `ticket` (
+  `id` INT(11) UNSIGNED NOT NULL AUTO_INCREMENT,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NULL,
+  `ticket` SMALLINT(11) UNSIGNED NOT NULL,
+  `action` VARCHAR(20) NOT NULL DEFAULT 'Message',
+  `message` TEXT NOT NULL,
+  `timestamp` DATETIME NOT NULL,
+  `recipient` VARCHAR(32) DEFAULT NULL,
+  `sender` VARCHAR(32) DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_ticket_act_recip` (`action`, `recipient`),
+  KEY `idx_ticket_act_send` (`action`, `sender`),
+  KEY `idx_ticket_tic_rid` (`ticket`, `round_id`),
+  KEY `idx_ticket_act_time_rid` (`action`, `timestamp`, `round_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `connection_log`;
+CREATE TABLE `connection_log` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `datetime` DATETIME DEFAULT NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NOT NULL,
+  `ckey` VARCHAR(45) DEFAULT NULL,
+  `ip` INT(10) UNSIGNED NOT NULL,
+  `computerid` VARCHAR(45) DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_connection_ckey` (`ckey`, `datetime`),
+  KEY `idx_connection_ip` (`ip`),
+  KEY `idx_connection_cid` (`computerid`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `achievements`;
+CREATE TABLE `achievements` (
+  `ckey` VARCHAR(32) NOT NULL,
+  `achievement_key` VARCHAR(32) NOT NULL,
+  `value` INT NULL,
+  `last_updated` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`ckey`, `achievement_key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `achievement_metadata`;
+CREATE TABLE `achievement_metadata` (
+  `achievement_key` VARCHAR(32) NOT NULL,
+  `achievement_version` SMALLINT UNSIGNED NOT NULL DEFAULT 0,
+  `achievement_type` ENUM('achievement','score','award') NULL DEFAULT NULL,
+  `achievement_name` VARCHAR(64) NULL DEFAULT NULL,
+  `achievement_description` VARCHAR(512) NULL DEFAULT NULL,
+  PRIMARY KEY (`achievement_key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `discord_links`;
+CREATE TABLE `discord_links` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `ckey` VARCHAR(32) NOT NULL,
+  `discord_id` BIGINT(20) DEFAULT NULL,
+  `timestamp` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `one_time_token` VARCHAR(100) NOT NULL,
+  `valid` TINYINT(1) NOT NULL DEFAULT 0,
+  PRIMARY KEY (`id`),
+  KEY `idx_discord_links_ckey` (`ckey`, `valid`),
+  KEY `idx_discord_links_discord_id` (`discord_id`, `valid`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `round`;
+CREATE TABLE `round` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `initialize_datetime` DATETIME NOT NULL,
+  `start_datetime` DATETIME NULL,
+  `shutdown_datetime` DATETIME NULL,
+  `end_datetime` DATETIME NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `commit_hash` CHAR(40) NULL,
+  `game_mode` VARCHAR(32) NULL,
+  `game_mode_result` VARCHAR(64) NULL,
+  `end_state` VARCHAR(64) NULL,
+  `shuttle_name` VARCHAR(64) NULL,
+  `map_name` VARCHAR(32) NULL,
+  `station_name` VARCHAR(80) NULL,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `feedback`;
+CREATE TABLE `feedback` (
+  `id` INT(11) UNSIGNED NOT NULL AUTO_INCREMENT,
+  `datetime` DATETIME NOT NULL,
+  `round_id` INT(11) UNSIGNED NOT NULL,
+  `key_name` VARCHAR(32) NOT NULL,
+  `key_type` ENUM('text','amount','tally','nested tally','associative') NOT NULL,
+  `version` TINYINT(3) UNSIGNED NOT NULL,
+  `json` JSON NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_feedback_round_key` (`round_id`, `key_name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `legacy_population`;
+CREATE TABLE `legacy_population` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `playercount` INT(11) DEFAULT NULL,
+  `admincount` INT(11) DEFAULT NULL,
+  `time` DATETIME NOT NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_population_round` (`round_id`, `time`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `death`;
+CREATE TABLE `death` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `pod` VARCHAR(50) NOT NULL,
+  `x_coord` SMALLINT(5) UNSIGNED NOT NULL,
+  `y_coord` SMALLINT(5) UNSIGNED NOT NULL,
+  `z_coord` SMALLINT(5) UNSIGNED NOT NULL,
+  `mapname` VARCHAR(32) NOT NULL,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NOT NULL,
+  `tod` DATETIME NOT NULL,
+  `job` VARCHAR(32) NOT NULL,
+  `special` VARCHAR(32) NULL DEFAULT NULL,
+  `name` VARCHAR(96) NOT NULL,
+  `byondkey` VARCHAR(32) NOT NULL,
+  `laname` VARCHAR(96) NULL DEFAULT NULL,
+  `lakey` VARCHAR(32) NULL DEFAULT NULL,
+  `bruteloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `brainloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `fireloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `oxyloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `toxloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `cloneloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `staminaloss` SMALLINT(5) UNSIGNED NOT NULL,
+  `last_words` VARCHAR(255) NULL DEFAULT NULL,
+  `suicide` TINYINT(1) NOT NULL DEFAULT '0',
+  PRIMARY KEY (`id`),
+  KEY `idx_death_round` (`round_id`),
+  KEY `idx_death_byondkey` (`byondkey`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+DROP TABLE IF EXISTS `citation`;
+CREATE TABLE `citation` (
+  `id` INT(11) NOT NULL AUTO_INCREMENT,
+  `server_ip` INT(10) UNSIGNED NOT NULL,
+  `server_port` SMALLINT(5) UNSIGNED NOT NULL,
+  `round_id` INT(11) UNSIGNED NULL,
+  `sender` VARCHAR(32) NULL DEFAULT NULL,
+  `sender_ic` VARCHAR(64) NOT NULL,
+  `recipient` VARCHAR(64) NOT NULL,
+  `crime` VARCHAR(64) NOT NULL,
+  `crime_desc` VARCHAR(512) NULL DEFAULT NULL,
+  `fine` INT(11) NULL DEFAULT NULL,
+  `paid` INT(11) NOT NULL DEFAULT '0',
+  `timestamp` DATETIME NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_citation_round` (`round_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;